@@ -0,0 +1,62 @@
+extern crate queues;
+
+use queues::{Deque, IsQueue};
+
+fn main() {
+    println!("\nDeque - typical usage");
+    println!("--");
+
+    println!("\nCreate a new empty deque:");
+    println!("let mut dq: Deque<isize> = Deque::new();");
+    let mut dq: Deque<isize> = Deque::new();
+
+    println!("\nAdd elements to it:");
+    println!("dq.add(1);");
+    println!("> {:?}", dq.add(1));
+    println!("dq.add(-2);");
+    println!("> {:?}", dq.add(-2));
+    println!("dq.add(3);");
+    println!("> {:?}", dq.add(3));
+
+    println!("\nCheck the deque's size:");
+    println!("dq.size();  // Should be 3");
+    println!("> {}", dq.size());
+
+    println!("\nRemove elements from it:");
+    println!("dq.remove();  // Should be Ok(1)");
+    println!("> {:?}", dq.remove());
+
+    println!("\nPut the element back at the front:");
+    println!("dq.add_front(1);");
+    dq.add_front(1);
+    println!("dq.peek_front();  // Should be Ok(1)");
+    println!("> {:?}", dq.peek_front());
+
+    println!("\nPeek at the back of the deque:");
+    println!("dq.peek_back();  // Should be Ok(3)");
+    println!("> {:?}", dq.peek_back());
+
+    println!("\nRemove an element from the back:");
+    println!("dq.remove_back();  // Should be Ok(3)");
+    println!("> {:?}", dq.remove_back());
+
+    println!("\nCheck the deque's size:");
+    println!("dq.size();  // Should be 2");
+    println!("> {}", dq.size());
+
+    println!("\nRemove more elements from it:");
+    println!("dq.remove_front();  // Should be Ok(1)");
+    println!("> {:?}", dq.remove_front());
+    println!("dq.remove_front();  // Should be Ok(-2)");
+    println!("> {:?}", dq.remove_front());
+
+    println!("\nPeek at the next element to be removed:");
+    println!("dq.peek();  // Should raise an error");
+    println!("> {:?}", dq.peek());
+
+    println!("\nAttempt to remove elements from it:");
+    println!("dq.remove();  // Should raise an error");
+    println!("> {:?}", dq.remove());
+
+    println!("\n--\n")
+}
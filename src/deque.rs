@@ -0,0 +1,292 @@
+use std::collections::VecDeque;
+
+use crate::{IsQueue, QueueError};
+
+/// A double-ended queue with a growable size and no limit on its capacity.
+///
+/// Elements can be added to and removed from either end. Through `IsQueue`,
+/// `add` appends to the back and `remove` takes from the front, so a `Deque`
+/// can stand in for a `Queue`.
+///
+/// # Type parameters
+/// - `T`: Any type that implements the `Clone` trait.
+///
+/// # Examples
+///
+/// ```
+/// # use queues::*;
+/// let mut dq: Deque<isize> = Deque::new();
+///
+/// // Add elements at both ends
+/// dq.add_back(2);
+/// dq.add_back(3);
+/// dq.add_front(1);
+///
+/// // Remove elements from both ends
+/// assert_eq!(dq.remove_front(), Ok(1));
+/// assert_eq!(dq.remove_back(), Ok(3));
+///
+/// // Peek at the remaining element
+/// assert_eq!(dq.peek_front(), Ok(2));
+/// assert_eq!(dq.peek_back(), Ok(2));
+///
+/// // Check the deque size
+/// assert_eq!(dq.size(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Deque<T: Clone> {
+    queue: VecDeque<T>,
+}
+
+impl<T: Clone> Deque<T> {
+    /// Create a new deque
+    ///
+    /// # Returns
+    /// A new, empty `Deque<T>`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let dq: Deque<isize> = Deque::new();
+    /// assert_eq!(dq.size(), 0);
+    /// ```
+    pub fn new() -> Deque<T> {
+        Deque { queue: VecDeque::new() }
+    }
+
+    /// Add an element to the back of the deque
+    ///
+    /// # Parameters
+    /// - `val`: Value to add to the deque
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut dq: Deque<isize> = Deque::new();
+    /// dq.add_back(1);
+    /// dq.add_back(2);
+    /// assert_eq!(dq.peek_back(), Ok(2));
+    /// ```
+    pub fn add_back(&mut self, val: T) {
+        self.queue.push_back(val);
+    }
+
+    /// Add an element to the front of the deque
+    ///
+    /// The element becomes the next one scheduled for removal, which makes
+    /// it possible to put back an element that was just removed.
+    ///
+    /// # Parameters
+    /// - `val`: Value to add to the deque
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut dq: Deque<isize> = Deque::new();
+    /// dq.add_back(2);
+    /// dq.add_front(1);
+    /// assert_eq!(dq.peek_front(), Ok(1));
+    /// ```
+    pub fn add_front(&mut self, val: T) {
+        self.queue.push_front(val);
+    }
+
+    /// Remove the element at the front of the deque
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The element at the front of the deque
+    /// - `Err(QueueError::Empty)`
+    ///
+    /// # Errors
+    /// Returns an error if the deque is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut dq: Deque<isize> = Deque::new();
+    /// assert_eq!(dq.remove_front(), Err(QueueError::Empty));
+    /// dq.add_back(1);
+    /// dq.add_back(2);
+    /// assert_eq!(dq.remove_front(), Ok(1));
+    /// ```
+    pub fn remove_front(&mut self) -> Result<T, QueueError> {
+        self.queue.pop_front().ok_or(QueueError::Empty)
+    }
+
+    /// Remove the element at the back of the deque
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The element at the back of the deque
+    /// - `Err(QueueError::Empty)`
+    ///
+    /// # Errors
+    /// Returns an error if the deque is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut dq: Deque<isize> = Deque::new();
+    /// assert_eq!(dq.remove_back(), Err(QueueError::Empty));
+    /// dq.add_back(1);
+    /// dq.add_back(2);
+    /// assert_eq!(dq.remove_back(), Ok(2));
+    /// ```
+    pub fn remove_back(&mut self) -> Result<T, QueueError> {
+        self.queue.pop_back().ok_or(QueueError::Empty)
+    }
+
+    /// Peek at the front of the deque
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The element at the front of the deque
+    /// - `Err(QueueError::Empty)`
+    ///
+    /// # Errors
+    /// Returns an error if the deque is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut dq: Deque<isize> = Deque::new();
+    /// assert_eq!(dq.peek_front(), Err(QueueError::Empty));
+    /// dq.add_back(1);
+    /// dq.add_back(2);
+    /// assert_eq!(dq.peek_front(), Ok(1));
+    /// ```
+    pub fn peek_front(&self) -> Result<T, QueueError> {
+        self.queue.front().cloned().ok_or(QueueError::Empty)
+    }
+
+    /// Peek at the back of the deque
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The element at the back of the deque
+    /// - `Err(QueueError::Empty)`
+    ///
+    /// # Errors
+    /// Returns an error if the deque is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut dq: Deque<isize> = Deque::new();
+    /// assert_eq!(dq.peek_back(), Err(QueueError::Empty));
+    /// dq.add_back(1);
+    /// dq.add_back(2);
+    /// assert_eq!(dq.peek_back(), Ok(2));
+    /// ```
+    pub fn peek_back(&self) -> Result<T, QueueError> {
+        self.queue.back().cloned().ok_or(QueueError::Empty)
+    }
+}
+
+impl<T: Clone> Default for Deque<T> {
+    /// Default deque initializer
+    ///
+    /// # Returns
+    /// A new, empty `Deque<T>`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let dq: Deque<isize> = Deque::default();
+    /// assert_eq!(dq.size(), 0);
+    /// ```
+    fn default() -> Deque<T> {
+        Deque::new()
+    }
+}
+
+impl<T: Clone> IsQueue<T> for Deque<T> {
+    /// Adds an element to the back of the deque
+    ///
+    /// # Parameters
+    /// - `val`: Value to add to the deque
+    ///
+    /// # Returns
+    /// `Ok(None)` as the element addition should always be successful
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut dq: Deque<isize> = Deque::new();
+    /// assert_eq!(dq.add(42), Ok(None));
+    /// assert_eq!(dq.peek_back(), Ok(42));
+    /// ```
+    fn add(&mut self, val: T) -> Result<Option<T>, &str> {
+        self.add_back(val);
+        Ok(None)
+    }
+
+    /// Removes the element at the front of the deque and returns it
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The oldest element in the deque
+    /// - `Error`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to remove an element from
+    /// an empty deque
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut dq: Deque<isize> = Deque::new();
+    /// dq.add(1);
+    /// dq.add(2);
+    /// assert_eq!(dq.remove(), Ok(1));
+    /// ```
+    fn remove(&mut self) -> Result<T, &str> {
+        self.remove_front().map_err(|_| "The deque is empty")
+    }
+
+    /// Peek at the front of the deque
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The next element scheduled for removal from the deque
+    /// - `Error`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to peek into an empty deque
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut dq: Deque<isize> = Deque::new();
+    /// dq.add(1);
+    /// dq.add(2);
+    /// assert_eq!(dq.peek(), Ok(1));
+    /// ```
+    fn peek(&self) -> Result<T, &str> {
+        self.peek_front().map_err(|_| "The deque is empty")
+    }
+
+    /// Gets the size of the deque
+    ///
+    /// # Returns
+    /// The number of elements in the deque
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut dq: Deque<isize> = Deque::new();
+    /// assert_eq!(dq.size(), 0);
+    /// dq.add_front(42);
+    /// assert_eq!(dq.size(), 1);
+    /// ```
+    fn size(&self) -> usize {
+        self.queue.len()
+    }
+}
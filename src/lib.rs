@@ -1,28 +1,30 @@
 //! # Queues
 //!
 //! `queues` provides a number of efficient FIFO Queue data structures for
-//! usage in your libraries. These are all implemented on top of rust's `Vector`
-//! type.
+//! usage in your libraries. These are all implemented on top of rust's
+//! `VecDeque` type.
 //!
 //! A queue is a linear data structure that commonly defines three methods:
 //!
 //! 1. `add`: Also called `queue` or `push`, this adds elements to the queue.
 //! 2. `remove`: Also called `deque` or `pop`, this removes the _oldest_
-//!     element from the queue.
+//!    element from the queue.
 //! 3. `peek`: Show the next element in the queue scheduled for removal.
 //!
 //! There are a number of variants of queues. In this crate, the available
 //! variants are:
 //!
 //! - `Queue<T>`: A simple FIFO queue with a growable size and no limit on its
-//!     capacity.
+//!   capacity.
 //! - `Buffer<T>`: A FIFO queue with with a limited capacity. The buffer can
-//!     have a growable size (up to the defined capacity), or it can be
-//!     initialized at capacity, with empty slots being occupied by default
-//!     values.
+//!   have a growable size (up to the defined capacity), or it can be
+//!   initialized at capacity, with empty slots being occupied by default
+//!   values.
 //! - `CircularBuffer<T>`: Similar to the buffer above, but allowing for
-//!     overflow. Any additions to the circular buffer that would exceed its
-//!     capacity causes its oldest element to be pushed out.
+//!   overflow. Any additions to the circular buffer that would exceed its
+//!   capacity causes its oldest element to be pushed out.
+//! - `Deque<T>`: A double-ended queue with no limit on its capacity, allowing
+//!   elements to be added and removed at either end.
 //!
 //! # Quick start
 //!
@@ -40,6 +42,14 @@
 //! queues = "1.0.2"
 //! ```
 //!
+//! Enable the optional `serde` feature to serialize and deserialize the
+//! queues with [serde](https://serde.rs):
+//!
+//! ```yaml
+//! [dependencies]
+//! queues = { version = "1.0.2", features = ["serde"] }
+//! ```
+//!
 //! In your files, import the crate and use it's members:
 //!
 //! ```rust
@@ -78,6 +88,7 @@
 //! - `buf` Buffer example
 //! - `cbuf` Circular buffer example
 //! - `cbuf_def` Circular buffer with default values example
+//! - `deque` Deque example
 //!
 //! ```bash
 //! > cd ${PROJECT_FOLDER}
@@ -135,6 +146,87 @@
 
 #![warn(missing_docs)]
 
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::TryInto;
+use std::hash::Hash;
+use std::iter::Sum;
+use std::ops::Sub;
+
+mod deque;
+#[cfg(feature = "serde")]
+mod serde_impl;
+
+pub use deque::Deque;
+
+/// Errors returned by queue operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueError {
+    /// The operation requires at least one element, but the queue is empty
+    Empty,
+    /// The operation would leave the queue with more elements than its
+    /// capacity allows
+    Full,
+    /// No element of the queue matches what the operation is looking for
+    NotFound,
+    /// An index or range lies outside of the elements of the queue
+    OutOfBounds,
+    /// The queue does not hold the number of elements the operation requires
+    SizeMismatch {
+        /// Number of elements required
+        expected: usize,
+        /// Number of elements held
+        actual: usize,
+    },
+}
+
+impl std::fmt::Display for QueueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            QueueError::Empty => write!(f, "The queue is empty"),
+            QueueError::Full => write!(f, "The queue is full"),
+            QueueError::NotFound => write!(f, "No matching element was found in the queue"),
+            QueueError::OutOfBounds => write!(f, "The index is out of bounds"),
+            QueueError::SizeMismatch { expected, actual } => {
+                write!(f, "Expected {} elements, but the queue holds {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for QueueError {}
+
+/// Describes how a queue behaves when an element is added at capacity
+///
+/// # Examples
+///
+/// ```
+/// # use queues::*;
+/// let queues: Vec<Box<dyn IsQueue<isize>>> = vec![
+///     Box::new(Queue::new()),
+///     Box::new(Buffer::new(3)),
+///     Box::new(CircularBuffer::new(3)),
+/// ];
+///
+/// let behaviors: Vec<_> = queues.iter().map(|q| q.overflow_behavior()).collect();
+/// assert_eq!(
+///     behaviors,
+///     vec![
+///         OverflowBehavior::Unbounded,
+///         OverflowBehavior::Reject,
+///         OverflowBehavior::EvictOldest
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowBehavior {
+    /// The queue has no capacity limit, so additions always succeed
+    Unbounded,
+    /// Additions to a full queue are rejected with an error
+    Reject,
+    /// Additions to a full queue push out its oldest element
+    EvictOldest,
+}
+
 /// Defines methods that would be expected on a queue data structure
 pub trait IsQueue<T: Clone> {
     /// Adds a new value to a queue
@@ -145,7 +237,7 @@ pub trait IsQueue<T: Clone> {
     /// # Returns
     /// - `Ok(_)`: If the element add was successful.
     ///     - `Some(T)`: If adding an element resulted in the removal of an
-    ///         existing one (in the case of a circular buffer, for instance)
+    ///       existing one (in the case of a circular buffer, for instance)
     ///     - `None`: Adding an element did not return any value
     /// - `Error`: If the element add was unsuccessful
     ///
@@ -185,6 +277,46 @@ pub trait IsQueue<T: Clone> {
     /// values when specified, which means that the `size` of a queue with
     /// default values should always be equal to its `capacity`
     fn size(&self) -> usize;
+
+    /// Checks whether the queue is empty
+    ///
+    /// # Returns
+    /// `true` if the `size` of the queue is zero. Note, default values count
+    /// towards the `size`, so a queue with default values is never empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut q: Queue<isize> = Queue::new();
+    /// assert!(q.is_empty());
+    /// q.add(1);
+    /// assert!(!q.is_empty());
+    ///
+    /// let buf: Buffer<isize> = Buffer::new(3);
+    /// assert!(buf.is_empty());
+    ///
+    /// let cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// assert!(cbuf.is_empty());
+    ///
+    /// let cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// assert!(!cbuf_def.is_empty());
+    /// ```
+    fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    /// Gets the behavior of the queue when an element is added at capacity
+    ///
+    /// Generic code can use this to decide whether to check for room before
+    /// adding, expect errors, or expect elements to be evicted.
+    ///
+    /// # Returns
+    /// The queue's `OverflowBehavior`. Defaults to
+    /// `OverflowBehavior::Unbounded`.
+    fn overflow_behavior(&self) -> OverflowBehavior {
+        OverflowBehavior::Unbounded
+    }
 }
 
 /// A simple FIFO queue with a growable size and no limit on its capacity.
@@ -218,9 +350,46 @@ pub trait IsQueue<T: Clone> {
 /// assert_eq!(q.size(), 2);
 /// # }
 /// ```
-#[derive(Debug)]
 pub struct Queue<T: Clone> {
-    queue: Vec<T>,
+    queue: VecDeque<T>,
+    on_add: Option<Hook<T>>,
+    on_remove: Option<Hook<T>>,
+}
+
+/// Callback invoked with a reference to an element entering or leaving a queue
+type Hook<T> = Box<dyn FnMut(&T) + Send>;
+
+/// Rearrange ring storage so that its elements form a single slice
+///
+/// Called after any operation that may wrap the elements around the end of
+/// the ring, so that slices of the elements can be handed out through
+/// `&self`. The copy costs O(`len`), so the cost of the operations calling
+/// this is amortized rather than constant.
+fn keep_contiguous<T>(queue: &mut VecDeque<T>) {
+    if !queue.as_slices().1.is_empty() {
+        // Reserving room for as many elements again means the ring cannot wrap
+        // for at least another `len` insertions, which keeps the copy rare. It
+        // does let the allocation grow to twice the number of elements.
+        queue.reserve(queue.len());
+        queue.make_contiguous();
+    }
+}
+
+/// Borrow the elements of ring storage kept contiguous by `keep_contiguous`
+///
+/// # Panics
+/// Panics if the storage is not contiguous, rather than returning only part
+/// of the elements
+fn contiguous<T>(queue: &VecDeque<T>) -> &[T] {
+    let (vals, wrapped) = queue.as_slices();
+    assert!(wrapped.is_empty(), "queue storage is not contiguous");
+    vals
+}
+
+impl<T: Clone + std::fmt::Debug> std::fmt::Debug for Queue<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Queue").field("queue", &self.queue).finish()
+    }
 }
 
 impl<T: Clone> Queue<T> {
@@ -237,437 +406,5508 @@ impl<T: Clone> Queue<T> {
     /// assert_eq!(q.size(), 0);
     /// ```
     pub fn new() -> Queue<T> {
-        Queue { queue: vec![] }
+        Queue {
+            queue: VecDeque::new(),
+            on_add: None,
+            on_remove: None,
+        }
     }
-}
 
-impl<T: Clone> Default for Queue<T> {
-    /// Default queue initializer
+    /// Find the first element in the queue matching a predicate
+    ///
+    /// Elements are searched from oldest to newest.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against
     ///
     /// # Returns
-    /// A new, empty `Queue<T>`
+    /// - `Some(&T)`: A reference to the first matching element
+    /// - `None`: If no element matches
     ///
     /// # Examples
     ///
     /// ```
+    /// # #[macro_use] extern crate queues;
     /// # use queues::*;
-    /// let q: Queue<isize> = Queue::default();
-    /// assert_eq!(q.size(), 0);
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3, 4];
+    /// assert_eq!(q.find(|&x| x > 2), Some(&3));
+    /// assert_eq!(q.find(|&x| x > 4), None);
+    /// # }
     /// ```
-    fn default() -> Queue<T> {
-        Queue { queue: vec![] }
+    pub fn find<F: FnMut(&T) -> bool>(&self, mut pred: F) -> Option<&T> {
+        self.queue.iter().find(|val| pred(val))
     }
-}
 
-impl<T: Clone> IsQueue<T> for Queue<T> {
-    /// Adds an element to a queue
+    /// Replace a range of elements in the queue
+    ///
+    /// Mirrors `Vec::splice`: the elements at the oldest-first positions in
+    /// `range` are replaced with the elements of `replacement`, which may be
+    /// longer or shorter than the range.
     ///
     /// # Parameters
-    /// - `val`: Value to add to the queue
+    /// - `range`: Oldest-first positions of the elements to replace
+    /// - `replacement`: Elements to insert in place of the removed ones
     ///
     /// # Returns
-    /// `Ok(None)` as the element addition should always be successful
+    /// The removed elements, oldest first
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds, or if its start is greater than
+    /// its end
     ///
     /// # Examples
     ///
     /// ```
+    /// # #[macro_use] extern crate queues;
     /// # use queues::*;
-    /// let mut q: Queue<isize> = Queue::new();
-    /// assert_eq!(q.add(42), Ok(None));
+    /// # fn main() {
+    /// // Larger replacement
+    /// let mut q = queue![1isize, 2, 3];
+    /// assert_eq!(q.splice(1..2, vec![7, 8, 9]), vec![2]);
+    /// assert_eq!(q.size(), 5);
+    /// assert_eq!(q.remove(), Ok(1));
+    /// assert_eq!(q.remove(), Ok(7));
+    ///
+    /// // Smaller replacement
+    /// let mut q = queue![1isize, 2, 3, 4];
+    /// assert_eq!(q.splice(0..3, vec![0]), vec![1, 2, 3]);
+    /// assert_eq!(q.remove(), Ok(0));
+    /// assert_eq!(q.remove(), Ok(4));
+    ///
+    /// // Empty replacement
+    /// let mut q = queue![1isize, 2, 3];
+    /// assert_eq!(q.splice(1..3, vec![]), vec![2, 3]);
     /// assert_eq!(q.size(), 1);
+    /// # }
     /// ```
-    fn add(&mut self, val: T) -> Result<Option<T>, &str> {
-        self.queue.push(val);
-        Ok(None)
+    pub fn splice<I: IntoIterator<Item = T>>(&mut self, range: std::ops::Range<usize>, replacement: I) -> Vec<T> {
+        let mut tail = self.queue.split_off(range.end);
+        let removed = self.queue.drain(range.start..).collect();
+        self.queue.extend(replacement);
+        self.queue.append(&mut tail);
+        keep_contiguous(&mut self.queue);
+        removed
     }
 
-    /// Removes an element from the queue and returns it
+    /// Register a callback to be invoked whenever an element is added
     ///
-    /// # Returns
-    /// - `Ok(T)`: The oldest element in the queue
-    /// - `Error`
+    /// The callback receives a reference to each element passed to `add`,
+    /// before it is stored. Registering a new callback replaces any existing
+    /// one. Callbacks must be `Send` so that the queue can still be moved to
+    /// another thread, but they need not be `Sync`.
     ///
-    /// # Errors
-    /// Returns an error if an attempt is made to remove an element from
-    /// an empty queue
+    /// # Parameters
+    /// - `hook`: Callback to invoke on each addition
     ///
     /// # Examples
     ///
     /// ```
     /// # use queues::*;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let added = Arc::new(Mutex::new(vec![]));
+    /// let log = Arc::clone(&added);
+    ///
     /// let mut q: Queue<isize> = Queue::new();
-    /// q.add(42);
-    /// assert_eq!(q.remove(), Ok(42));
-    /// assert_eq!(q.size(), 0);
+    /// q.set_on_add(move |&val| log.lock().unwrap().push(val));
+    /// q.add(1);
+    /// q.add(2);
+    /// assert_eq!(*added.lock().unwrap(), vec![1, 2]);
     /// ```
-    fn remove(&mut self) -> Result<T, &str> {
-        if !self.queue.is_empty() {
-            Ok(self.queue.remove(0usize))
-        } else {
-            Err("The queue is empty")
-        }
+    pub fn set_on_add<F: FnMut(&T) + Send + 'static>(&mut self, hook: F) {
+        self.on_add = Some(Box::new(hook));
     }
 
-    /// Peek at the head of the queue
+    /// Register a callback to be invoked whenever an element is removed
     ///
-    /// # Returns
-    /// - `Ok(T)`: The next element scheduled for removal from the queue
-    /// - `Error`
+    /// The callback receives a reference to each element taken out by
+    /// `remove`, before it is returned. Registering a new callback replaces
+    /// any existing one. Callbacks must be `Send` so that the queue can still
+    /// be moved to another thread, but they need not be `Sync`.
     ///
-    /// # Errors
-    /// Returns an error if an attempt is made to peek into an empty queue
+    /// # Parameters
+    /// - `hook`: Callback to invoke on each removal
     ///
     /// # Examples
     ///
     /// ```
     /// # use queues::*;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let removed = Arc::new(Mutex::new(vec![]));
+    /// let log = Arc::clone(&removed);
+    ///
     /// let mut q: Queue<isize> = Queue::new();
-    /// q.add(42);
-    /// assert_eq!(q.peek(), Ok(42));
+    /// q.set_on_remove(move |&val| log.lock().unwrap().push(val));
+    /// q.add(1);
+    /// q.add(2);
+    /// assert_eq!(*removed.lock().unwrap(), vec![]);
+    /// assert_eq!(q.remove(), Ok(1));
+    /// assert_eq!(*removed.lock().unwrap(), vec![1]);
     /// ```
-    fn peek(&self) -> Result<T, &str> {
-        match self.queue.first() {
-            Some(val) => Ok(val.clone()),
-            None => Err("The Queue is empty"),
-        }
+    pub fn set_on_remove<F: FnMut(&T) + Send + 'static>(&mut self, hook: F) {
+        self.on_remove = Some(Box::new(hook));
     }
 
-    /// Gets the size of the queue
+    /// Remove all elements from the queue, newest first
+    ///
+    /// The queue is empty once the returned iterator is dropped, even if it
+    /// was not fully consumed.
     ///
     /// # Returns
-    /// The number of elements in the queue
+    /// An iterator yielding the removed elements from newest to oldest
     ///
     /// # Examples
     ///
     /// ```
+    /// # #[macro_use] extern crate queues;
     /// # use queues::*;
-    /// let mut q: Queue<isize> = Queue::new();
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 3];
+    /// assert_eq!(q.drain_rev().collect::<Vec<_>>(), vec![3, 2, 1]);
     /// assert_eq!(q.size(), 0);
-    /// let _ = q.add(42);
-    /// assert_eq!(q.size(), 1);
+    /// # }
     /// ```
-    fn size(&self) -> usize {
-        self.queue.len()
+    pub fn drain_rev(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.queue.drain(..).rev()
     }
-}
-
-/// Creates a new `Queue<T>`
-///
-/// Delegates to the default queue initializer. Note that the elements are
-/// added to the queue from left to right, therefore the first element in the
-/// list of parameters passed to the macro is considered the 'oldest' element
-/// in the queue.
-///
-/// # Example
-/// ```
-/// # #[macro_use]
-/// # extern crate queues;
-/// # use queues::*;
-///
-/// # fn main() {
-/// let q = queue![3isize, 4, 5];
-/// assert_eq!(q.peek(), Ok(3));
-///
-/// let q_empty: Queue<isize> = queue![];
-/// assert_eq!(q_empty.size(), 0);
-/// # }
-/// ```
-#[macro_export]
-macro_rules! queue {
-    () => { Queue::new() };
-    ($($x:expr),+) => {
-        {
-            let mut temp_q = Queue::default();
-            $(
-                let _ = temp_q.add($x);
-            )*
-            temp_q
-        }
-    };
-}
-
-/// A FIFO buffer with a growable size and a capacity limit
-///
-/// # Type parameters
-/// - `T`: Any type that implements the `Clone` trait.
-///
-/// # Examples
-///
-/// ```
-/// # use queues::*;
-/// let mut buf = Buffer::new(3);
-///
-/// // Add some elements
-/// assert_eq!(buf.add(6), Ok(None));
-/// assert_eq!(buf.add(7), Ok(None));
-///
-/// // Remove an element
-/// assert_eq!(buf.remove(), Ok(6));
-///
-/// // Peek at the next element scheduled for removal
-/// assert_eq!(buf.peek(), Ok(7));
-///
-/// // Check the queue size
-/// assert_eq!(buf.size(), 1);
-/// ```
-#[derive(Debug)]
-pub struct Buffer<T: Clone> {
-    queue: Vec<T>,
-    capacity: usize,
-}
 
-impl<T: Clone> Buffer<T> {
-    /// Create a new buffer
+    /// Add a batch of elements to the head of the queue
     ///
-    /// # Returns
-    /// A new, empty `Buffer<T>`
+    /// The batch keeps its internal order, so its first element becomes the
+    /// new head of the queue.
+    ///
+    /// # Parameters
+    /// - `vals`: Elements to insert at the head of the queue
     ///
     /// # Examples
     ///
     /// ```
+    /// # #[macro_use] extern crate queues;
     /// # use queues::*;
-    /// let buf: Buffer<isize> = Buffer::new(3);
-    /// assert_eq!(buf.size(), 0);
+    /// # fn main() {
+    /// let mut q = queue![3isize, 4];
+    /// q.extend_front(vec![1, 2]);
+    /// assert_eq!(q.size(), 4);
+    /// assert_eq!(q.remove(), Ok(1));
+    /// assert_eq!(q.remove(), Ok(2));
+    /// assert_eq!(q.remove(), Ok(3));
+    /// assert_eq!(q.remove(), Ok(4));
+    /// # }
     /// ```
-    pub fn new(capacity: usize) -> Buffer<T> {
-        Buffer {
-            queue: vec![],
-            capacity,
-        }
+    pub fn extend_front<I: IntoIterator<Item = T>>(&mut self, vals: I) {
+        let mut front: VecDeque<T> = vals.into_iter().collect();
+        front.append(&mut self.queue);
+        keep_contiguous(&mut front);
+        self.queue = front;
     }
 
-    /// Gets the capacity of the buffer
+    /// Remove elements from the head of the queue while they match a predicate
+    ///
+    /// Removal stops at the first element that does not match, which is left
+    /// in the queue.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against
     ///
     /// # Returns
-    /// The number of allowed elements in the buffer
+    /// The removed elements, oldest first
     ///
     /// # Examples
     ///
     /// ```
+    /// # #[macro_use] extern crate queues;
     /// # use queues::*;
-    /// let mut buf: Buffer<isize> = Buffer::new(5);
-    /// assert_eq!(buf.capacity(), 5);
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 5, 1];
+    /// assert_eq!(q.drain_front_while(|&x| x < 3), vec![1, 2]);
+    /// assert_eq!(q.size(), 2);
+    /// assert_eq!(q.peek(), Ok(5));
+    /// # }
     /// ```
-    pub fn capacity(&self) -> usize {
-        self.capacity
+    pub fn drain_front_while<F: FnMut(&T) -> bool>(&mut self, mut pred: F) -> Vec<T> {
+        let count = self.queue.iter().take_while(|val| pred(val)).count();
+        self.queue.drain(..count).collect()
     }
-}
 
-impl<T: Clone> IsQueue<T> for Buffer<T> {
-    /// Adds an element to a buffer
+    /// Merge several queues into one, taking an element from each in turn
+    ///
+    /// Elements are taken from the head of each queue in the order the queues
+    /// are given. Exhausted queues are skipped until all of them are empty.
     ///
     /// # Parameters
-    /// - `val`: Value to add to the buffer
+    /// - `queues`: Queues to merge
     ///
     /// # Returns
-    /// - `Ok(None)`: Element addition was successful
-    /// - `Error`
-    ///
-    /// # Errors
-    /// Returns an error if an attempt is made to add an element to a full
-    /// buffer
+    /// A new `Queue<T>` holding the interleaved elements
     ///
     /// # Examples
     ///
     /// ```
-    /// use queues::*;
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = Queue::round_robin(vec![queue![1isize, 2, 3], queue![10, 20], queue![100]]);
+    /// assert_eq!(q.size(), 6);
+    /// assert_eq!(q.remove(), Ok(1));
+    /// assert_eq!(q.remove(), Ok(10));
+    /// assert_eq!(q.remove(), Ok(100));
+    /// assert_eq!(q.remove(), Ok(2));
+    /// assert_eq!(q.remove(), Ok(20));
+    /// assert_eq!(q.remove(), Ok(3));
     ///
-    /// let mut buf: Buffer<isize> = Buffer::new(3);
-    /// assert_eq!(buf.add(42), Ok(None));
+    /// let q_empty: Queue<isize> = Queue::round_robin(vec![]);
+    /// assert_eq!(q_empty.size(), 0);
+    /// # }
     /// ```
-    fn add(&mut self, val: T) -> Result<Option<T>, &str> {
-        if self.queue.len() < self.capacity {
-            self.queue.push(val);
-            Ok(None)
-        } else {
-            Err("The buffer is full")
+    pub fn round_robin<I: IntoIterator<Item = Queue<T>>>(queues: I) -> Queue<T> {
+        let mut sources: Vec<_> = queues.into_iter().map(|q| q.queue.into_iter()).collect();
+        let mut merged = Queue::new();
+
+        while !sources.is_empty() {
+            sources.retain_mut(|source| match source.next() {
+                Some(val) => {
+                    merged.queue.push_back(val);
+                    true
+                }
+                None => false,
+            });
         }
+
+        merged
     }
 
-    /// Removes an element from the buffer and returns it.
+    /// Remove the head of the queue and peek at the new head
     ///
     /// # Returns
-    /// - `Ok(T)`: The oldest element in the buffer
-    /// - `Error`
+    /// - `Ok((T, Option<T>))`: The removed oldest element, and the element
+    ///   that is now at the head of the queue (`None` if the queue is now
+    ///   empty)
+    /// - `Err(QueueError::Empty)`
     ///
     /// # Errors
     /// Returns an error if an attempt is made to remove an element from
-    /// an empty buffer
+    /// an empty queue
     ///
     /// # Examples
     ///
     /// ```
+    /// # #[macro_use] extern crate queues;
     /// # use queues::*;
-    /// let mut buf: Buffer<isize> = Buffer::new(3);
-    /// buf.add(42);
-    /// assert_eq!(buf.remove(), Ok(42));
-    /// assert_eq!(buf.size(), 0);
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 3];
+    /// assert_eq!(q.remove_and_peek(), Ok((1, Some(2))));
+    /// assert_eq!(q.remove_and_peek(), Ok((2, Some(3))));
+    /// assert_eq!(q.remove_and_peek(), Ok((3, None)));
+    /// assert_eq!(q.remove_and_peek(), Err(QueueError::Empty));
+    /// # }
     /// ```
-    fn remove(&mut self) -> Result<T, &str> {
-        if !self.queue.is_empty() {
-            Ok(self.queue.remove(0usize))
-        } else {
-            Err("The buffer is empty")
-        }
+    pub fn remove_and_peek(&mut self) -> Result<(T, Option<T>), QueueError> {
+        let val = self.remove().map_err(|_| QueueError::Empty)?;
+        Ok((val, self.queue.front().cloned()))
     }
 
-    /// Peek at the head of the buffer
+    /// Borrow the head of the queue
     ///
-    /// # Returns
-    /// - `Ok(T)`: The next element scheduled for removal from the buffer
-    /// - `Error`
+    /// Unlike `peek`, this neither clones the element nor returns an error.
     ///
-    /// # Errors
-    /// Returns an error if an attempt is made to peek into an empty buffer
+    /// # Returns
+    /// - `Some(&T)`: A reference to the next element scheduled for removal
+    /// - `None`: If the queue is empty
     ///
     /// # Examples
     ///
     /// ```
     /// # use queues::*;
-    /// let mut buf: Buffer<isize> = Buffer::new(3);
-    /// buf.add(42);
-    /// assert_eq!(buf.peek(), Ok(42));
+    /// #[derive(Clone)]
+    /// struct Job {
+    ///     id: usize,
+    ///     payload: Vec<u8>,
+    /// }
+    ///
+    /// let mut q: Queue<Job> = Queue::new();
+    /// assert!(q.try_peek_ref().is_none());
+    ///
+    /// q.add(Job { id: 7, payload: vec![0; 1024] });
+    /// if let Some(job) = q.try_peek_ref() {
+    ///     assert_eq!(job.id, 7);
+    ///     assert_eq!(job.payload.len(), 1024);
+    /// }
+    /// assert_eq!(q.size(), 1);
     /// ```
-    fn peek(&self) -> Result<T, &str> {
-        match self.queue.first() {
-            Some(val) => Ok(val.clone()),
-            None => Err("The buffer is empty"),
+    pub fn try_peek_ref(&self) -> Option<&T> {
+        self.queue.front()
+    }
+
+    /// Split the queue into buffers holding up to `n` elements each
+    ///
+    /// Each buffer has a capacity of `n`, and the buffers are filled in FIFO
+    /// order, so only the last one may be partially filled.
+    ///
+    /// # Parameters
+    /// - `n`: Capacity of each buffer
+    ///
+    /// # Returns
+    /// The buffers, holding the oldest elements first
+    ///
+    /// # Panics
+    /// Panics if `n` is zero
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3, 4, 5, 6, 7];
+    /// let buffers = q.into_buffers(3);
+    /// assert_eq!(buffers.len(), 3);
+    /// assert_eq!(buffers.iter().map(|buf| buf.size()).collect::<Vec<_>>(), vec![3, 3, 1]);
+    /// assert!(buffers.iter().all(|buf| buf.capacity() == 3));
+    /// assert_eq!(buffers[1].peek(), Ok(4));
+    /// # }
+    /// ```
+    pub fn into_buffers(self, n: usize) -> Vec<Buffer<T>> {
+        assert!(n != 0, "buffer capacity must be non-zero");
+
+        let mut vals = self.queue.into_iter().peekable();
+        let mut buffers = vec![];
+        while vals.peek().is_some() {
+            buffers.push(Buffer {
+                queue: vals.by_ref().take(n).collect(),
+                ..Buffer::new(n)
+            });
+        }
+        buffers
+    }
+
+    /// Remove all elements from the queue in batches, passing each to `f`
+    ///
+    /// Batches hold up to `n` of the oldest elements, so only the last batch
+    /// may hold fewer than `n` elements. The queue is empty afterwards.
+    ///
+    /// # Parameters
+    /// - `n`: Maximum number of elements per batch
+    /// - `f`: Function to call with each batch
+    ///
+    /// # Panics
+    /// Panics if `n` is zero
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 3, 4, 5];
+    /// let mut sums = vec![];
+    /// q.for_each_batch(2, |batch| sums.push(batch.iter().sum::<isize>()));
+    /// assert_eq!(sums, vec![3, 7, 5]);
+    /// assert_eq!(q.size(), 0);
+    /// # }
+    /// ```
+    pub fn for_each_batch<F: FnMut(Vec<T>)>(&mut self, n: usize, mut f: F) {
+        assert!(n != 0, "batch size must be non-zero");
+
+        while !self.queue.is_empty() {
+            let count = n.min(self.queue.len());
+            f(self.queue.drain(..count).collect());
+        }
+    }
+
+    /// Copy the newest elements of the queue into a `CircularBuffer<T>`
+    ///
+    /// This clones up to `capacity` of the most recent elements, in order,
+    /// into a fresh circular buffer. The queue itself is left untouched.
+    ///
+    /// # Parameters
+    /// - `capacity`: Capacity of the circular buffer
+    ///
+    /// # Returns
+    /// A new `CircularBuffer<T>` holding the newest elements of the queue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3, 4, 5];
+    /// let mut ring = q.ring_view(3);
+    /// assert_eq!(ring.capacity(), 3);
+    /// assert_eq!(ring.remove(), Ok(3));
+    /// assert_eq!(ring.remove(), Ok(4));
+    /// assert_eq!(ring.remove(), Ok(5));
+    /// assert_eq!(q.size(), 5);
+    /// # }
+    /// ```
+    pub fn ring_view(&self, capacity: usize) -> CircularBuffer<T> {
+        CircularBuffer::from_slice_tail(capacity, contiguous(&self.queue))
+    }
+
+    /// Create a queue from its run-length encoding
+    ///
+    /// Reverses `Queue::run_length_encode`, expanding each
+    /// `(value, run_length)` pair into `run_length` copies of `value`.
+    ///
+    /// # Parameters
+    /// - `runs`: Queue of `(value, run_length)` pairs
+    ///
+    /// # Returns
+    /// A new `Queue<T>` holding the expanded elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = Queue::run_length_decode(queue![('a', 2), ('b', 1)]);
+    /// assert_eq!(q.size(), 3);
+    /// assert_eq!(q.remove(), Ok('a'));
+    /// assert_eq!(q.remove(), Ok('a'));
+    /// assert_eq!(q.remove(), Ok('b'));
+    /// # }
+    /// ```
+    pub fn run_length_decode(runs: Queue<(T, usize)>) -> Queue<T> {
+        let mut decoded = Queue::new();
+        for (val, count) in runs.queue {
+            decoded.queue.extend(std::iter::repeat_n(val, count));
+        }
+        decoded
+    }
+
+    /// Remove the oldest elements until the queue holds `target` elements
+    ///
+    /// Nothing is removed if the queue already holds `target` elements or
+    /// fewer.
+    ///
+    /// # Parameters
+    /// - `target`: Number of elements to leave in the queue
+    ///
+    /// # Returns
+    /// The removed elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 3, 4, 5];
+    /// assert_eq!(q.drain_to_size(10), vec![]);
+    /// assert_eq!(q.drain_to_size(2), vec![1, 2, 3]);
+    /// assert_eq!(q.size(), 2);
+    /// assert_eq!(q.drain_to_size(0), vec![4, 5]);
+    /// assert_eq!(q.size(), 0);
+    /// # }
+    /// ```
+    pub fn drain_to_size(&mut self, target: usize) -> Vec<T> {
+        let excess = self.queue.len().saturating_sub(target);
+        self.queue.drain(..excess).collect()
+    }
+
+    /// Split off the elements before the first one matching a predicate
+    ///
+    /// If an element matches, the elements ahead of it are removed and
+    /// returned as a new queue, leaving the matching element at the head of
+    /// this queue. If no element matches, the queue is left unchanged.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against
+    ///
+    /// # Returns
+    /// - `Some(Queue<T>)`: The elements ahead of the first match
+    /// - `None`: If no element matches
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 0, 3, 0];
+    /// let mut frame = q.split_when(|&x| x == 0).unwrap();
+    /// assert_eq!(frame.size(), 2);
+    /// assert_eq!(frame.remove(), Ok(1));
+    /// assert_eq!(frame.remove(), Ok(2));
+    /// assert_eq!(q.size(), 3);
+    /// assert_eq!(q.peek(), Ok(0));
+    ///
+    /// // No match
+    /// let mut q = queue![1isize, 2, 3];
+    /// assert!(q.split_when(|&x| x == 0).is_none());
+    /// assert_eq!(q.size(), 3);
+    /// # }
+    /// ```
+    pub fn split_when<F: FnMut(&T) -> bool>(&mut self, pred: F) -> Option<Queue<T>> {
+        let index = self.queue.iter().position(pred)?;
+        let mut front = Queue::new();
+        front.queue = self.queue.drain(..index).collect();
+        Some(front)
+    }
+
+    /// Remove consecutive elements that are considered the same
+    ///
+    /// Forwards to `Vec::dedup_by`: `same` is called with each element and
+    /// the last element kept before it, and the element is removed if it
+    /// returns `true`. The first element of each run is kept, and the order
+    /// of the queue is preserved.
+    ///
+    /// # Parameters
+    /// - `same`: Function deciding whether two elements are the same
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// #[derive(Clone)]
+    /// struct Event {
+    ///     user_id: usize,
+    ///     action: &'static str,
+    /// }
+    ///
+    /// let mut q: Queue<Event> = Queue::new();
+    /// q.add(Event { user_id: 1, action: "login" });
+    /// q.add(Event { user_id: 1, action: "click" });
+    /// q.add(Event { user_id: 2, action: "login" });
+    /// q.add(Event { user_id: 1, action: "logout" });
+    ///
+    /// q.dedup_by(|a, b| a.user_id == b.user_id);
+    /// assert_eq!(q.size(), 3);
+    /// assert_eq!(q.remove().unwrap().action, "login");
+    /// assert_eq!(q.remove().unwrap().user_id, 2);
+    /// assert_eq!(q.remove().unwrap().action, "logout");
+    /// ```
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, same: F) {
+        let mut vals: Vec<T> = std::mem::take(&mut self.queue).into();
+        vals.dedup_by(same);
+        self.queue = vals.into();
+    }
+
+    /// Rotate the queue so that the first element matching a predicate is at
+    /// the head
+    ///
+    /// No elements are removed: the elements ahead of the match are moved to
+    /// the tail of the queue, preserving their cyclic order.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the queue was rotated
+    /// - `Err(QueueError::NotFound)`
+    ///
+    /// # Errors
+    /// Returns an error, leaving the queue unchanged, if no element matches
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 3, 4];
+    /// assert_eq!(q.rotate_to(|&x| x == 3), Ok(()));
+    /// assert_eq!(q.as_slice(), &[3, 4, 1, 2]);
+    /// assert_eq!(q.remove(), Ok(3));
+    /// assert_eq!(q.remove(), Ok(4));
+    /// assert_eq!(q.remove(), Ok(1));
+    /// assert_eq!(q.remove(), Ok(2));
+    ///
+    /// let mut q = queue![1isize, 2];
+    /// assert_eq!(q.rotate_to(|&x| x == 3), Err(QueueError::NotFound));
+    /// assert_eq!(q.peek(), Ok(1));
+    /// # }
+    /// ```
+    pub fn rotate_to<F: FnMut(&T) -> bool>(&mut self, pred: F) -> Result<(), QueueError> {
+        let index = self.queue.iter().position(pred).ok_or(QueueError::NotFound)?;
+        self.queue.rotate_left(index);
+        keep_contiguous(&mut self.queue);
+        Ok(())
+    }
+
+    /// Rotate the queue until its head matches a predicate
+    ///
+    /// Heads that do not match are moved to the tail one at a time, which
+    /// lets a scheduler cyclically skip over elements that are not ready yet.
+    /// If a full cycle passes without a match, the queue is back in its
+    /// original order. The result is the same as `Queue::rotate_to`.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each head against
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the head of the queue now matches
+    /// - `Err(QueueError::NotFound)`
+    ///
+    /// # Errors
+    /// Returns an error, leaving the queue in its original order, if no
+    /// element matches
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 3, 4, 6];
+    /// assert_eq!(q.rotate_until(|&x| x % 2 == 0), Ok(()));
+    /// assert_eq!(q.iter_cloned().collect::<Vec<_>>(), vec![4, 6, 1, 3]);
+    ///
+    /// assert_eq!(q.rotate_until(|&x| x > 10), Err(QueueError::NotFound));
+    /// assert_eq!(q.iter_cloned().collect::<Vec<_>>(), vec![4, 6, 1, 3]);
+    /// # }
+    /// ```
+    pub fn rotate_until<F: FnMut(&T) -> bool>(&mut self, pred: F) -> Result<(), QueueError> {
+        self.rotate_to(pred)
+    }
+
+    /// Split the queue into its oldest element and the rest
+    ///
+    /// Mirrors `slice::split_first`, borrowing the elements without cloning.
+    ///
+    /// # Returns
+    /// - `Some((&T, &[T]))`: The head of the queue, and the elements behind
+    ///   it, oldest first
+    /// - `None`: If the queue is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3];
+    /// assert_eq!(q.split_first(), Some((&1, &[2, 3][..])));
+    ///
+    /// let q_empty: Queue<isize> = queue![];
+    /// assert_eq!(q_empty.split_first(), None);
+    /// # }
+    /// ```
+    pub fn split_first(&self) -> Option<(&T, &[T])> {
+        contiguous(&self.queue).split_first()
+    }
+
+    /// Split the queue into its newest element and the rest
+    ///
+    /// Mirrors `slice::split_last`, borrowing the elements without cloning.
+    ///
+    /// # Returns
+    /// - `Some((&T, &[T]))`: The tail of the queue, and the elements ahead
+    ///   of it, oldest first
+    /// - `None`: If the queue is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3];
+    /// assert_eq!(q.split_last(), Some((&3, &[1, 2][..])));
+    ///
+    /// let q_empty: Queue<isize> = queue![];
+    /// assert_eq!(q_empty.split_last(), None);
+    /// # }
+    /// ```
+    pub fn split_last(&self) -> Option<(&T, &[T])> {
+        contiguous(&self.queue).split_last()
+    }
+
+    /// Iterate over this queue followed by another one
+    ///
+    /// Borrows both queues, so neither needs to be merged or consumed.
+    ///
+    /// # Parameters
+    /// - `other`: Queue whose elements follow those of this queue
+    ///
+    /// # Returns
+    /// An iterator over the elements of this queue, oldest first, followed by
+    /// those of `other`, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let first = queue![1isize, 2];
+    /// let second = queue![3isize, 4];
+    /// assert_eq!(first.chain(&second).collect::<Vec<_>>(), vec![&1, &2, &3, &4]);
+    /// # }
+    /// ```
+    pub fn chain<'a>(&'a self, other: &'a Queue<T>) -> impl Iterator<Item = &'a T> {
+        self.queue.iter().chain(other.queue.iter())
+    }
+
+    /// Remove all elements from the queue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 3];
+    /// q.clear();
+    /// assert_eq!(q.size(), 0);
+    /// assert!(q.peek().is_err());
+    /// # }
+    /// ```
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Remove all elements from the queue and return them
+    ///
+    /// # Returns
+    /// The removed elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 3];
+    /// assert_eq!(q.drain(), vec![1, 2, 3]);
+    /// assert_eq!(q.size(), 0);
+    /// assert!(q.peek().is_err());
+    /// # }
+    /// ```
+    pub fn drain(&mut self) -> Vec<T> {
+        std::mem::take(&mut self.queue).into()
+    }
+
+    /// Release memory the queue no longer needs
+    ///
+    /// After removing most of the elements of a large queue, its allocation
+    /// stays at its peak; this hands the unused part back to the allocator.
+    /// The elements and their order are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut q: Queue<isize> = Queue::new();
+    /// for i in 0..1000 {
+    ///     q.add(i);
+    /// }
+    /// while q.size() > 3 {
+    ///     q.remove();
+    /// }
+    ///
+    /// q.shrink_to_fit();
+    /// assert!(q.capacity() < 1000);
+    /// assert_eq!(q.to_vec(), vec![997, 998, 999]);
+    ///
+    /// // Shrinking after removing from the head keeps the elements in one slice
+    /// let mut q: Queue<isize> = Queue::new();
+    /// for i in 0..16 {
+    ///     q.add(i);
+    /// }
+    /// for _ in 0..4 {
+    ///     q.remove();
+    /// }
+    /// q.shrink_to_fit();
+    /// assert_eq!(q.as_slice().len(), q.size());
+    /// assert_eq!(q.as_slice(), &(4..16).collect::<Vec<_>>()[..]);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.queue.shrink_to_fit();
+        // Shrinking can wrap the elements around the end of the ring. They are
+        // rearranged in place, as `keep_contiguous` would grow the allocation
+        // straight back.
+        self.queue.make_contiguous();
+    }
+
+    /// Iterate over clones of the elements in the queue
+    ///
+    /// The queue itself is left untouched.
+    ///
+    /// # Returns
+    /// An iterator over owned copies of the elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3];
+    /// assert_eq!(q.iter_cloned().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// assert_eq!(q.size(), 3);
+    /// # }
+    /// ```
+    pub fn iter_cloned(&self) -> impl Iterator<Item = T> + '_ {
+        self.queue.iter().cloned()
+    }
+
+    /// Get a copy of the queue's contents as a vector
+    ///
+    /// Unlike `into_iter`, the queue itself is left untouched.
+    ///
+    /// # Returns
+    /// A vector of cloned elements, with the oldest at index 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3];
+    /// assert_eq!(q.to_vec(), vec![1, 2, 3]);
+    /// assert_eq!(q.size(), 3);
+    /// # }
+    /// ```
+    pub fn to_vec(&self) -> Vec<T> {
+        self.queue.iter().cloned().collect()
+    }
+
+    /// View the queue's contents as a slice
+    ///
+    /// This avoids the allocation of `to_vec`. The slice is a live view that
+    /// borrows the queue, so it cannot outlive the next mutation.
+    ///
+    /// # Returns
+    /// A slice of the elements, with the oldest at index 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut q: Queue<isize> = Queue::new();
+    /// q.add(1);
+    /// q.add(2);
+    /// q.add(3);
+    /// assert_eq!(q.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        contiguous(&self.queue)
+    }
+
+    /// Clone the leading elements of the queue while they match a predicate
+    ///
+    /// This is the read-only counterpart of `drain_front_while`: the queue is
+    /// left unchanged.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against, oldest first
+    ///
+    /// # Returns
+    /// Clones of the leading matching elements, oldest first, stopping at the
+    /// first element that does not match
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 5, 3];
+    /// assert_eq!(q.take_while(|&x| x < 4), vec![1, 2]);
+    /// assert_eq!(q.size(), 4);
+    /// # }
+    /// ```
+    pub fn take_while<F: FnMut(&T) -> bool>(&self, mut pred: F) -> Vec<T> {
+        self.queue.iter().take_while(|val| pred(val)).cloned().collect()
+    }
+
+    /// Get a reference to the element at a position in the queue
+    ///
+    /// Position `0` is the head of the queue, the element `peek` returns.
+    /// The queue is left unchanged.
+    ///
+    /// # Parameters
+    /// - `index`: Oldest-first position of the element
+    ///
+    /// # Returns
+    /// - `Some(&T)`: The element at `index`
+    /// - `None`: If `index` is out of bounds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3];
+    /// assert_eq!(q.get(0), Some(&q.peek().unwrap()));
+    /// assert_eq!(q.get(2), Some(&3));
+    /// assert_eq!(q.get(3), None);
+    /// # }
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.queue.get(index)
+    }
+
+    /// Remove all elements matching a predicate from the queue
+    ///
+    /// This is the counterpart of keeping only the matching elements: the
+    /// matches are removed and returned, and the order of the remaining
+    /// elements is preserved.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against
+    ///
+    /// # Returns
+    /// The removed elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 3, 4, 5, 6];
+    /// assert_eq!(q.remove_matching(|&x| x % 2 == 1), vec![1, 3, 5]);
+    /// assert_eq!(q.iter_cloned().collect::<Vec<_>>(), vec![2, 4, 6]);
+    /// # }
+    /// ```
+    pub fn remove_matching<F: FnMut(&T) -> bool>(&mut self, pred: F) -> Vec<T> {
+        let (removed, kept): (Vec<T>, Vec<T>) = std::mem::take(&mut self.queue).into_iter().partition(pred);
+        self.queue = kept.into();
+        removed
+    }
+
+    /// Keep only the elements of the queue matching a predicate
+    ///
+    /// The order of the kept elements is preserved. Unlike `remove_matching`,
+    /// the dropped elements are not returned.
+    ///
+    /// # Parameters
+    /// - `f`: Predicate to test each element against
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// // Drop from the middle
+    /// let mut q = queue![1isize, 2, 3];
+    /// q.retain(|&x| x != 2);
+    /// assert_eq!(q.to_vec(), vec![1, 3]);
+    ///
+    /// // Drop the head
+    /// let mut q = queue![1isize, 2, 3];
+    /// q.retain(|&x| x != 1);
+    /// assert_eq!(q.peek(), Ok(2));
+    ///
+    /// // Drop the tail
+    /// let mut q = queue![1isize, 2, 3];
+    /// q.retain(|&x| x != 3);
+    /// assert_eq!(q.to_vec(), vec![1, 2]);
+    /// # }
+    /// ```
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        self.queue.retain(f);
+    }
+
+    /// Iterate over the elements of the queue
+    ///
+    /// The queue is borrowed, not consumed or modified.
+    ///
+    /// # Returns
+    /// An iterator over references to the elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3];
+    /// assert_eq!(q.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    /// assert_eq!(q.size(), 3);
+    /// assert_eq!(q.peek(), Ok(1));
+    /// # }
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        contiguous(&self.queue).iter()
+    }
+
+    /// Remove the element at a position, replacing it with the newest element
+    ///
+    /// This is an O(1) fast path that sacrifices FIFO order: the newest
+    /// element is moved into the removed element's position, rather than
+    /// shifting every element behind it.
+    ///
+    /// # Parameters
+    /// - `index`: Oldest-first position of the element to remove
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The removed element
+    /// - `Err(QueueError::OutOfBounds)`
+    ///
+    /// # Errors
+    /// Returns an error if `index` is not less than the queue's `size`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 3, 4];
+    /// assert_eq!(q.swap_remove(1), Ok(2));
+    /// assert_eq!(q.iter_cloned().collect::<Vec<_>>(), vec![1, 4, 3]);
+    /// assert_eq!(q.swap_remove(3), Err(QueueError::OutOfBounds));
+    /// # }
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, QueueError> {
+        self.queue.swap_remove_back(index).ok_or(QueueError::OutOfBounds)
+    }
+
+    /// Peek at the tail of the queue
+    ///
+    /// The tail is the most recently added element, the last one scheduled
+    /// for removal.
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The newest element in the queue
+    /// - `Err(QueueError::Empty)`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to peek into an empty queue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q: Queue<isize> = queue![];
+    /// assert_eq!(q.peek_back(), Err(QueueError::Empty));
+    ///
+    /// q.add(1);
+    /// q.add(2);
+    /// assert_eq!(q.peek(), Ok(1));
+    /// assert_eq!(q.peek_back(), Ok(2));
+    /// # }
+    /// ```
+    pub fn peek_back(&self) -> Result<T, QueueError> {
+        self.queue.back().cloned().ok_or(QueueError::Empty)
+    }
+
+    /// Drain the queue into buckets keyed by `key_fn`
+    ///
+    /// Every element is removed from the queue and placed in the bucket for
+    /// its key. Within each bucket, elements keep their FIFO order.
+    ///
+    /// # Returns
+    /// A map from each key to the elements that produced it, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 3, 4];
+    /// let groups = q.drain_grouped(|x| x % 2 == 0);
+    /// assert_eq!(groups[&true], vec![2, 4]);
+    /// assert_eq!(groups[&false], vec![1, 3]);
+    /// assert_eq!(q.size(), 0);
+    /// # }
+    /// ```
+    pub fn drain_grouped<K: Hash + Eq, F: FnMut(&T) -> K>(&mut self, mut key_fn: F) -> HashMap<K, Vec<T>> {
+        let mut groups: HashMap<K, Vec<T>> = HashMap::new();
+        for val in std::mem::take(&mut self.queue) {
+            groups.entry(key_fn(&val)).or_default().push(val);
+        }
+        groups
+    }
+
+    /// Reserve memory for at least `additional` more elements
+    ///
+    /// This is purely about allocation: a queue has no limit on the number of
+    /// elements it holds. Reserving ahead of a burst of additions avoids
+    /// repeated reallocations as the queue grows.
+    ///
+    /// # Parameters
+    /// - `additional`: Number of elements to make room for
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut q: Queue<isize> = Queue::new();
+    /// q.reserve(100);
+    /// let allocated = q.capacity();
+    /// assert!(allocated >= 100);
+    ///
+    /// for i in 0..100 {
+    ///     q.add(i);
+    /// }
+    /// assert_eq!(q.capacity(), allocated);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.queue.reserve(additional);
+    }
+
+    /// Gets the number of elements the queue can hold without reallocating
+    ///
+    /// Unlike `Buffer::capacity`, this is not a limit: the queue reallocates
+    /// to hold more elements as needed.
+    ///
+    /// # Returns
+    /// The allocated capacity of the queue, which is at least its `size`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3];
+    /// assert!(q.capacity() >= q.size());
+    /// # }
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+}
+
+impl<T: Clone + PartialOrd> Queue<T> {
+    /// Check whether the queue is monotonically increasing
+    ///
+    /// Elements are compared from oldest to newest. An empty or
+    /// single-element queue is trivially monotonic.
+    ///
+    /// # Returns
+    /// `true` if every element is greater than or equal to the one before it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// assert!(queue![1isize, 2, 2, 3].is_monotonic_increasing());
+    /// assert!(!queue![1isize, 3, 2].is_monotonic_increasing());
+    /// # }
+    /// ```
+    pub fn is_monotonic_increasing(&self) -> bool {
+        self.queue.iter().zip(self.queue.iter().skip(1)).all(|(a, b)| a <= b)
+    }
+
+    /// Check whether the queue is monotonically decreasing
+    ///
+    /// Elements are compared from oldest to newest. An empty or
+    /// single-element queue is trivially monotonic.
+    ///
+    /// # Returns
+    /// `true` if every element is less than or equal to the one before it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// assert!(queue![3isize, 2, 2, 1].is_monotonic_decreasing());
+    /// assert!(!queue![3isize, 1, 2].is_monotonic_decreasing());
+    /// # }
+    /// ```
+    pub fn is_monotonic_decreasing(&self) -> bool {
+        self.queue.iter().zip(self.queue.iter().skip(1)).all(|(a, b)| a >= b)
+    }
+
+    /// Get the rank of a value among the elements of the queue
+    ///
+    /// The rank is the number of elements strictly less than the value, which
+    /// is the position it would take if inserted into a sorted copy of the
+    /// queue. The queue does not need to be sorted.
+    ///
+    /// # Parameters
+    /// - `val`: Value to rank
+    ///
+    /// # Returns
+    /// The number of elements less than `val`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 5, 2, 4];
+    /// assert_eq!(q.rank(&3), 2);
+    /// assert_eq!(q.rank(&1), 0);
+    /// # }
+    /// ```
+    pub fn rank(&self, val: &T) -> usize {
+        self.queue.iter().filter(|&el| el < val).count()
+    }
+}
+
+impl<T: Clone + Eq + Hash> Queue<T> {
+    /// Count the occurrences of each distinct element in the queue
+    ///
+    /// # Returns
+    /// A map from each distinct element to the number of times it occurs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # use std::collections::HashMap;
+    /// # fn main() {
+    /// let q = queue![1isize, 1, 2, 3, 3, 3];
+    /// let counts = q.tally();
+    /// assert_eq!(counts, vec![(1, 2), (2, 1), (3, 3)].into_iter().collect::<HashMap<_, _>>());
+    /// # }
+    /// ```
+    pub fn tally(&self) -> HashMap<T, usize> {
+        let mut counts = HashMap::new();
+        for val in &self.queue {
+            *counts.entry(val.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Remove all but the last occurrence of each distinct element
+    ///
+    /// The surviving elements keep their relative order, each at the position
+    /// of its last occurrence. This is the opposite of keeping the first
+    /// occurrence, as `dedup_within_window` does over the whole queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 1, 3, 2];
+    /// q.dedup_keep_last();
+    /// assert_eq!(q.to_vec(), vec![1, 3, 2]);
+    ///
+    /// // Keeping the first occurrences instead
+    /// let mut q = queue![1isize, 2, 1, 3, 2];
+    /// q.dedup_within_window(q.size());
+    /// assert_eq!(q.to_vec(), vec![1, 2, 3]);
+    /// # }
+    /// ```
+    pub fn dedup_keep_last(&mut self) {
+        let mut seen = HashSet::new();
+        let mut kept: VecDeque<T> = VecDeque::with_capacity(self.queue.len());
+        while let Some(val) = self.queue.pop_back() {
+            if seen.insert(val.clone()) {
+                kept.push_front(val);
+            }
+        }
+        keep_contiguous(&mut kept);
+        self.queue = kept;
+    }
+
+    /// Check whether two queues hold the same elements, ignoring order
+    ///
+    /// The queues are compared as multisets: each distinct element must occur
+    /// the same number of times in both. Use `==` to also compare the order.
+    ///
+    /// # Parameters
+    /// - `other`: Queue to compare against
+    ///
+    /// # Returns
+    /// `true` if both queues hold the same elements with the same
+    /// multiplicities
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 2, 3];
+    /// assert!(q.eq_unordered(&queue![2isize, 3, 1, 2]));
+    /// assert!(q != queue![2isize, 3, 1, 2]);
+    ///
+    /// // Multiplicities differ
+    /// assert!(!q.eq_unordered(&queue![1isize, 2, 3, 3]));
+    /// assert!(!q.eq_unordered(&queue![1isize, 2, 3]));
+    /// # }
+    /// ```
+    pub fn eq_unordered(&self, other: &Queue<T>) -> bool {
+        self.queue.len() == other.queue.len() && self.tally() == other.tally()
+    }
+}
+
+impl<T: Clone + PartialEq> Queue<T> {
+    /// Remove elements equal to one of the `window` elements kept before them
+    ///
+    /// Each element is compared against the last `window` elements that
+    /// survived deduplication, and removed if it equals any of them. A window
+    /// of 1 only removes consecutive duplicates, while a window at least as
+    /// large as the queue removes all repeated values. The order of the
+    /// surviving elements is preserved.
+    ///
+    /// # Parameters
+    /// - `window`: Number of preceding elements to compare against
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2, 1, 3, 1];
+    /// q.dedup_within_window(1);
+    /// assert_eq!(q.size(), 5);
+    ///
+    /// let mut q = queue![1isize, 2, 1, 3, 1];
+    /// q.dedup_within_window(3);
+    /// assert_eq!(q.size(), 3);
+    /// assert_eq!(q.remove(), Ok(1));
+    /// assert_eq!(q.remove(), Ok(2));
+    /// assert_eq!(q.remove(), Ok(3));
+    /// # }
+    /// ```
+    pub fn dedup_within_window(&mut self, window: usize) {
+        let mut kept: Vec<T> = Vec::with_capacity(self.queue.len());
+        for val in self.queue.drain(..) {
+            if !kept[kept.len().saturating_sub(window)..].contains(&val) {
+                kept.push(val);
+            }
+        }
+        self.queue = kept.into();
+    }
+
+    /// Run-length encode the queue
+    ///
+    /// Consecutive equal elements are collapsed into a single
+    /// `(value, run_length)` pair, preserving the order of the queue. The
+    /// encoding can be reversed with `Queue::run_length_decode`.
+    ///
+    /// # Returns
+    /// A new queue of `(value, run_length)` pairs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 1, 1, 2, 3, 3];
+    /// let mut runs = q.run_length_encode();
+    /// assert_eq!(runs.size(), 3);
+    /// assert_eq!(runs.remove(), Ok((1, 3)));
+    /// assert_eq!(runs.remove(), Ok((2, 1)));
+    /// assert_eq!(runs.remove(), Ok((3, 2)));
+    ///
+    /// // Round trip
+    /// let mut decoded = Queue::run_length_decode(q.run_length_encode());
+    /// assert_eq!(decoded.size(), 6);
+    /// for expected in vec![1, 1, 1, 2, 3, 3] {
+    ///     assert_eq!(decoded.remove(), Ok(expected));
+    /// }
+    /// # }
+    /// ```
+    pub fn run_length_encode(&self) -> Queue<(T, usize)> {
+        let mut runs: Queue<(T, usize)> = Queue::new();
+        for val in &self.queue {
+            match runs.queue.back_mut() {
+                Some((last, count)) if last == val => *count += 1,
+                _ => runs.queue.push_back((val.clone(), 1)),
+            }
+        }
+        runs
+    }
+
+    /// Check whether the queue contains a value
+    ///
+    /// # Parameters
+    /// - `val`: Value to search for
+    ///
+    /// # Returns
+    /// `true` if any element of the queue is equal to `val`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3];
+    /// assert!(q.contains(&2));
+    /// assert!(!q.contains(&4));
+    /// # }
+    /// ```
+    pub fn contains(&self, val: &T) -> bool {
+        self.queue.contains(val)
+    }
+}
+
+impl<T: Clone, E: Clone> Queue<Result<T, E>> {
+    /// Convert a queue of results into a result of a queue
+    ///
+    /// Mirrors collecting an iterator of results: the elements are checked
+    /// from oldest to newest, stopping at the first error.
+    ///
+    /// # Returns
+    /// - `Ok(Queue<T>)`: A queue of the `Ok` values, in the same order, if
+    ///   every element is `Ok`
+    /// - `Err(E)`: The oldest `Err` in the queue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q: Queue<Result<isize, &str>> = queue![Ok(1), Ok(2)];
+    /// let mut values = q.into_result().unwrap();
+    /// assert_eq!(values.remove(), Ok(1));
+    /// assert_eq!(values.remove(), Ok(2));
+    ///
+    /// let q: Queue<Result<isize, &str>> = queue![Ok(1), Err("first"), Ok(3), Err("second")];
+    /// assert_eq!(q.into_result().unwrap_err(), "first");
+    /// # }
+    /// ```
+    pub fn into_result(self) -> Result<Queue<T>, E> {
+        let mut values = Queue::new();
+        values.queue = self.queue.into_iter().collect::<Result<_, _>>()?;
+        Ok(values)
+    }
+}
+
+impl<T: Clone> Default for Queue<T> {
+    /// Default queue initializer
+    ///
+    /// # Returns
+    /// A new, empty `Queue<T>`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let q: Queue<isize> = Queue::default();
+    /// assert_eq!(q.size(), 0);
+    /// ```
+    fn default() -> Queue<T> {
+        Queue {
+            queue: VecDeque::new(),
+            on_add: None,
+            on_remove: None,
+        }
+    }
+}
+
+impl<T: Clone> IsQueue<T> for Queue<T> {
+    /// Adds an element to a queue
+    ///
+    /// # Parameters
+    /// - `val`: Value to add to the queue
+    ///
+    /// # Returns
+    /// `Ok(None)` as the element addition should always be successful
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut q: Queue<isize> = Queue::new();
+    /// assert_eq!(q.add(42), Ok(None));
+    /// assert_eq!(q.size(), 1);
+    /// ```
+    fn add(&mut self, val: T) -> Result<Option<T>, &str> {
+        if let Some(hook) = self.on_add.as_mut() {
+            hook(&val);
+        }
+        self.queue.push_back(val);
+        keep_contiguous(&mut self.queue);
+        Ok(None)
+    }
+
+    /// Removes an element from the queue and returns it
+    ///
+    /// Removal takes amortized constant time, regardless of the size of the
+    /// queue. Popping the element is constant time, but the queue keeps its
+    /// elements in a single slice, and the occasional O(n) rearranging this
+    /// needs is spread across the operations that change the queue.
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The oldest element in the queue
+    /// - `Error`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to remove an element from
+    /// an empty queue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut q: Queue<isize> = Queue::new();
+    /// q.add(42);
+    /// assert_eq!(q.remove(), Ok(42));
+    /// assert_eq!(q.size(), 0);
+    ///
+    /// // Interleaved adds and removes keep FIFO order, and the elements stay
+    /// // viewable as a single slice
+    /// let mut next = 0;
+    /// for i in 0..100_000 {
+    ///     q.add(i);
+    ///     assert_eq!(q.as_slice().len(), q.size());
+    ///     if i % 3 != 0 {
+    ///         assert_eq!(q.remove(), Ok(next));
+    ///         next += 1;
+    ///     }
+    /// }
+    /// while let Ok(val) = q.remove() {
+    ///     assert_eq!(val, next);
+    ///     next += 1;
+    /// }
+    /// assert_eq!(next, 100_000);
+    /// ```
+    fn remove(&mut self) -> Result<T, &str> {
+        match self.queue.pop_front() {
+            Some(val) => {
+                if let Some(hook) = self.on_remove.as_mut() {
+                    hook(&val);
+                }
+                Ok(val)
+            }
+            None => Err("The queue is empty"),
+        }
+    }
+
+    /// Peek at the head of the queue
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The next element scheduled for removal from the queue
+    /// - `Error`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to peek into an empty queue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut q: Queue<isize> = Queue::new();
+    /// q.add(42);
+    /// assert_eq!(q.peek(), Ok(42));
+    /// ```
+    fn peek(&self) -> Result<T, &str> {
+        match self.queue.front() {
+            Some(val) => Ok(val.clone()),
+            None => Err("The Queue is empty"),
+        }
+    }
+
+    /// Gets the size of the queue
+    ///
+    /// # Returns
+    /// The number of elements in the queue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut q: Queue<isize> = Queue::new();
+    /// assert_eq!(q.size(), 0);
+    /// let _ = q.add(42);
+    /// assert_eq!(q.size(), 1);
+    /// ```
+    fn size(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+impl<T: Clone> IntoIterator for Queue<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    /// Consumes the queue, iterating over its elements
+    ///
+    /// # Returns
+    /// An iterator over the elements of the queue, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut q: Queue<isize> = Queue::new();
+    /// q.add(1);
+    /// q.add(2);
+    /// q.add(3);
+    ///
+    /// let mut order = vec![];
+    /// for val in q {
+    ///     order.push(val);
+    /// }
+    /// assert_eq!(order, vec![1, 2, 3]);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        Vec::from(self.queue).into_iter()
+    }
+}
+
+impl<T: Clone> Extend<T> for Queue<T> {
+    /// Adds each element of an iterator to the queue
+    ///
+    /// Elements are added in iterator order, so earlier elements are older.
+    ///
+    /// # Parameters
+    /// - `iter`: Elements to add to the queue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let mut q = queue![1isize, 2];
+    /// q.extend(vec![3, 4]);
+    /// assert_eq!(q.remove(), Ok(1));
+    /// assert_eq!(q.remove(), Ok(2));
+    /// assert_eq!(q.remove(), Ok(3));
+    /// assert_eq!(q.remove(), Ok(4));
+    /// # }
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for val in iter {
+            let _ = self.add(val);
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq for Queue<T> {
+    /// Compares two queues for equality
+    ///
+    /// Two queues are equal when they hold equal elements in the same FIFO
+    /// order. Registered `on_add` and `on_remove` callbacks are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// assert_eq!(queue![1isize, 2], queue![1isize, 2]);
+    /// assert_ne!(queue![1isize, 2], queue![2isize, 1]);
+    /// # }
+    /// ```
+    fn eq(&self, other: &Queue<T>) -> bool {
+        self.queue == other.queue
+    }
+}
+
+impl<T: Clone + Eq> Eq for Queue<T> {}
+
+impl<T: Clone> std::ops::Index<usize> for Queue<T> {
+    type Output = T;
+
+    /// Gets a reference to the element at a position in the queue
+    ///
+    /// Position `0` is the head of the queue, the element `peek` returns.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3];
+    /// assert_eq!(q[0], q.peek().unwrap());
+    /// assert_eq!(q[2], 3);
+    /// # }
+    /// ```
+    ///
+    /// ```should_panic
+    /// # #[macro_use] extern crate queues;
+    /// # use queues::*;
+    /// # fn main() {
+    /// let q = queue![1isize, 2, 3];
+    /// let _ = q[3];
+    /// # }
+    /// ```
+    fn index(&self, index: usize) -> &T {
+        let size = self.queue.len();
+        self.queue
+            .get(index)
+            .unwrap_or_else(|| panic!("index out of bounds: the size is {} but the index is {}", size, index))
+    }
+}
+
+/// Creates a new `Queue<T>`
+///
+/// Delegates to the default queue initializer. Note that the elements are
+/// added to the queue from left to right, therefore the first element in the
+/// list of parameters passed to the macro is considered the 'oldest' element
+/// in the queue.
+///
+/// # Example
+/// ```
+/// # #[macro_use]
+/// # extern crate queues;
+/// # use queues::*;
+///
+/// # fn main() {
+/// let q = queue![3isize, 4, 5];
+/// assert_eq!(q.peek(), Ok(3));
+///
+/// let q_empty: Queue<isize> = queue![];
+/// assert_eq!(q_empty.size(), 0);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! queue {
+    () => { Queue::new() };
+    ($($x:expr),+) => {
+        {
+            let mut temp_q = Queue::default();
+            $(
+                let _ = temp_q.add($x);
+            )*
+            temp_q
+        }
+    };
+}
+
+/// A FIFO buffer with a growable size and a capacity limit
+///
+/// # Type parameters
+/// - `T`: Any type that implements the `Clone` trait.
+///
+/// # Examples
+///
+/// ```
+/// # use queues::*;
+/// let mut buf = Buffer::new(3);
+///
+/// // Add some elements
+/// assert_eq!(buf.add(6), Ok(None));
+/// assert_eq!(buf.add(7), Ok(None));
+///
+/// // Remove an element
+/// assert_eq!(buf.remove(), Ok(6));
+///
+/// // Peek at the next element scheduled for removal
+/// assert_eq!(buf.peek(), Ok(7));
+///
+/// // Check the queue size
+/// assert_eq!(buf.size(), 1);
+/// ```
+pub struct Buffer<T: Clone> {
+    queue: VecDeque<T>,
+    capacity: usize,
+    watermarks: Option<Watermarks>,
+    on_low: Option<WatermarkHook>,
+    on_high: Option<WatermarkHook>,
+}
+
+/// Callback invoked with the size of a buffer when it crosses a watermark
+type WatermarkHook = Box<dyn FnMut(usize) + Send>;
+
+/// Fill watermarks of a buffer, and which of them was crossed last
+#[derive(Debug, Clone, Copy)]
+struct Watermarks {
+    low: usize,
+    high: usize,
+    filled: bool,
+}
+
+impl<T: Clone + std::fmt::Debug> std::fmt::Debug for Buffer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Buffer")
+            .field("queue", &self.queue)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl<T: Clone> Buffer<T> {
+    /// Create a new buffer
+    ///
+    /// # Returns
+    /// A new, empty `Buffer<T>`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let buf: Buffer<isize> = Buffer::new(3);
+    /// assert_eq!(buf.size(), 0);
+    /// ```
+    pub fn new(capacity: usize) -> Buffer<T> {
+        Buffer {
+            queue: VecDeque::new(),
+            capacity,
+            watermarks: None,
+            on_low: None,
+            on_high: None,
+        }
+    }
+
+    /// Gets the capacity of the buffer
+    ///
+    /// # Returns
+    /// The number of allowed elements in the buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(5);
+    /// assert_eq!(buf.capacity(), 5);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Find the first element in the buffer matching a predicate
+    ///
+    /// Elements are searched from oldest to newest.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against
+    ///
+    /// # Returns
+    /// - `Some(&T)`: A reference to the first matching element
+    /// - `None`: If no element matches
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(5);
+    /// assert_eq!(buf.find(|&x| x > 2), Some(&5));
+    /// assert_eq!(buf.find(|&x| x > 5), None);
+    /// ```
+    pub fn find<F: FnMut(&T) -> bool>(&self, mut pred: F) -> Option<&T> {
+        self.queue.iter().find(|val| pred(val))
+    }
+
+    /// Convert the buffer into a `CircularBuffer<T>`
+    ///
+    /// The circular buffer shares the capacity and contents of this buffer,
+    /// in the same FIFO order, and has no default value.
+    ///
+    /// # Returns
+    /// A new `CircularBuffer<T>` holding the buffer's elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(2);
+    /// buf.add(1);
+    /// buf.add(2);
+    ///
+    /// let mut cbuf = buf.into_circular();
+    /// assert_eq!(cbuf.capacity(), 2);
+    /// assert_eq!(cbuf.add(3), Ok(Some(1)));
+    ///
+    /// let mut buf = cbuf.into_buffer();
+    /// assert_eq!(buf.capacity(), 2);
+    /// assert_eq!(buf.add(4), Err("The buffer is full"));
+    /// assert_eq!(buf.remove(), Ok(2));
+    /// assert_eq!(buf.remove(), Ok(3));
+    /// ```
+    pub fn into_circular(self) -> CircularBuffer<T> {
+        CircularBuffer {
+            queue: self.queue,
+            capacity: self.capacity,
+            default_value: None,
+            newest_age: None,
+        }
+    }
+
+    /// Add an element to the buffer, replacing the oldest one if it is full
+    ///
+    /// This opts a single addition into circular buffer behaviour, without
+    /// changing how `add` behaves on the buffer.
+    ///
+    /// # Parameters
+    /// - `val`: Value to add to the buffer
+    ///
+    /// # Returns
+    /// - `Some(T)`: The evicted oldest element, if the buffer was full
+    /// - `None`: If the buffer had room for the added element
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(2);
+    /// assert_eq!(buf.add_or_replace_oldest(1), None);
+    /// assert_eq!(buf.add_or_replace_oldest(2), None);
+    ///
+    /// // The buffer is now full
+    /// assert_eq!(buf.add(3), Err("The buffer is full"));
+    /// assert_eq!(buf.add_or_replace_oldest(3), Some(1));
+    /// assert_eq!(buf.size(), 2);
+    /// assert_eq!(buf.peek(), Ok(2));
+    /// ```
+    pub fn add_or_replace_oldest(&mut self, val: T) -> Option<T> {
+        self.queue.push_back(val);
+        keep_contiguous(&mut self.queue);
+        let evicted = if self.queue.len() > self.capacity {
+            self.queue.pop_front()
+        } else {
+            None
+        };
+        self.check_watermarks();
+        evicted
+    }
+
+    /// Remove all elements from the buffer, newest first
+    ///
+    /// The buffer is empty once the returned iterator is dropped, even if it
+    /// was not fully consumed.
+    ///
+    /// # Returns
+    /// An iterator yielding the removed elements from newest to oldest
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.add(3);
+    /// assert_eq!(buf.drain_rev().collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// assert_eq!(buf.size(), 0);
+    /// ```
+    pub fn drain_rev(&mut self) -> impl Iterator<Item = T> + '_ {
+        let vals = std::mem::take(&mut self.queue);
+        self.check_watermarks();
+        vals.into_iter().rev()
+    }
+
+    /// Remove all complete batches of `n` elements from the buffer
+    ///
+    /// Any remaining elements that do not fill a complete batch are left in
+    /// the buffer.
+    ///
+    /// # Parameters
+    /// - `n`: Number of elements per batch
+    ///
+    /// # Returns
+    /// The removed batches, oldest first
+    ///
+    /// # Panics
+    /// Panics if `n` is zero
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(8);
+    /// for i in 1..=7 {
+    ///     buf.add(i);
+    /// }
+    ///
+    /// assert_eq!(buf.flush_chunks(2), vec![vec![1, 2], vec![3, 4], vec![5, 6]]);
+    /// assert_eq!(buf.size(), 1);
+    /// assert_eq!(buf.peek(), Ok(7));
+    /// ```
+    pub fn flush_chunks(&mut self, n: usize) -> Vec<Vec<T>> {
+        assert!(n != 0, "batch size must be non-zero");
+
+        let count = self.queue.len() / n;
+        let mut batches = self.queue.drain(..count * n);
+        let flushed = (0..count).map(|_| batches.by_ref().take(n).collect()).collect();
+        drop(batches);
+        self.check_watermarks();
+        flushed
+    }
+
+    /// Remove elements from the head of the buffer while they match a predicate
+    ///
+    /// Removal stops at the first element that does not match, which is left
+    /// in the buffer.
+    ///
+    /// The capacity taken up by the removed elements is freed for new
+    /// additions.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against
+    ///
+    /// # Returns
+    /// The removed elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(4);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.add(5);
+    /// buf.add(1);
+    /// assert_eq!(buf.add(6), Err("The buffer is full"));
+    ///
+    /// assert_eq!(buf.drain_front_while(|&x| x < 3), vec![1, 2]);
+    /// assert_eq!(buf.peek(), Ok(5));
+    /// assert_eq!(buf.add(6), Ok(None));
+    /// ```
+    pub fn drain_front_while<F: FnMut(&T) -> bool>(&mut self, mut pred: F) -> Vec<T> {
+        let count = self.queue.iter().take_while(|val| pred(val)).count();
+        let drained = self.queue.drain(..count).collect();
+        self.check_watermarks();
+        drained
+    }
+
+    /// Convert the buffer into a `CircularBuffer<T>` with default values
+    ///
+    /// The circular buffer shares the capacity of this buffer. Its oldest
+    /// slots hold the buffer's elements, in the same FIFO order, and any
+    /// remaining slots hold the default value, so that its `size` is equal
+    /// to its `capacity`.
+    ///
+    /// # Parameters
+    /// - `default_value`: Default value of the circular buffer
+    ///
+    /// # Returns
+    /// A new `CircularBuffer<T>` holding the buffer's elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// // Half-full source buffer
+    /// let mut buf: Buffer<isize> = Buffer::new(4);
+    /// buf.add(1);
+    /// buf.add(2);
+    ///
+    /// let mut cbuf_def = buf.into_circular_with_default(0);
+    /// assert_eq!(cbuf_def.size(), 4);
+    /// assert_eq!(cbuf_def.remove(), Ok(1));
+    /// assert_eq!(cbuf_def.remove(), Ok(2));
+    /// assert_eq!(cbuf_def.remove(), Ok(0));
+    ///
+    /// // Full source buffer
+    /// let mut buf: Buffer<isize> = Buffer::new(2);
+    /// buf.add(1);
+    /// buf.add(2);
+    ///
+    /// let mut cbuf_def = buf.into_circular_with_default(0);
+    /// assert_eq!(cbuf_def.size(), 2);
+    /// assert_eq!(cbuf_def.add(3), Ok(Some(1)));
+    /// ```
+    pub fn into_circular_with_default(self, default_value: T) -> CircularBuffer<T> {
+        let mut queue = self.queue;
+        let newest_age = match queue.len() {
+            0 => None,
+            size => Some(self.capacity - size),
+        };
+        queue.resize(self.capacity, default_value.clone());
+        keep_contiguous(&mut queue);
+
+        CircularBuffer {
+            queue,
+            capacity: self.capacity,
+            default_value: Some(default_value),
+            newest_age,
+        }
+    }
+
+    /// Borrow the head of the buffer
+    ///
+    /// Unlike `peek`, this neither clones the element nor returns an error.
+    ///
+    /// # Returns
+    /// - `Some(&T)`: A reference to the next element scheduled for removal
+    /// - `None`: If the buffer is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<String> = Buffer::new(2);
+    /// assert!(buf.try_peek_ref().is_none());
+    ///
+    /// buf.add(String::from("head"));
+    /// if let Some(head) = buf.try_peek_ref() {
+    ///     assert_eq!(head.len(), 4);
+    /// }
+    /// ```
+    pub fn try_peek_ref(&self) -> Option<&T> {
+        self.queue.front()
+    }
+
+    /// Lower the capacity of the buffer to its current size
+    ///
+    /// This does not remove any elements, it only lowers the capacity limit,
+    /// leaving the buffer full. No further elements can be added until some
+    /// are removed, which makes this useful for applying backpressure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(5);
+    /// for i in 0..4 {
+    ///     buf.add(i);
+    /// }
+    /// buf.remove();
+    /// buf.remove();
+    ///
+    /// buf.shrink_capacity_to_size();
+    /// assert_eq!(buf.capacity(), 2);
+    /// assert_eq!(buf.size(), 2);
+    /// assert_eq!(buf.add(4), Err("The buffer is full"));
+    /// ```
+    pub fn shrink_capacity_to_size(&mut self) {
+        self.capacity = self.queue.len();
+    }
+
+    /// Gets the fraction of the buffer's capacity that is still free
+    ///
+    /// # Returns
+    /// A value between `0.0` (full) and `1.0` (empty). A buffer with no
+    /// capacity is always full, and returns `0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(4);
+    /// assert_eq!(buf.remaining_as_ratio(), 1.0);
+    /// buf.add(1);
+    /// assert_eq!(buf.remaining_as_ratio(), 0.75);
+    /// ```
+    pub fn remaining_as_ratio(&self) -> f64 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        (self.capacity - self.queue.len()) as f64 / self.capacity as f64
+    }
+
+    /// Set the low and high fill watermarks of the buffer
+    ///
+    /// The callback registered with `set_on_high` fires when the size of the
+    /// buffer rises to `high` or above, and the one registered with
+    /// `set_on_low` fires when the size then falls to `low` or below. Each
+    /// fires once per crossing: after the high callback has fired, it does
+    /// not fire again until the low one has, and vice versa, so the size can
+    /// move back and forth between the watermarks without firing either.
+    ///
+    /// Every operation that changes the size of the buffer is checked, and
+    /// the callbacks receive the size the buffer was left at. A buffer that is
+    /// already at or above `high` when the watermarks are set counts as
+    /// filled, without either callback firing.
+    ///
+    /// # Parameters
+    /// - `low`: Size at which the buffer is considered drained
+    /// - `high`: Size at which the buffer is considered filled
+    ///
+    /// # Panics
+    /// Panics if `low` is greater than `high`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let events = Arc::new(Mutex::new(vec![]));
+    /// let (high_log, low_log) = (Arc::clone(&events), Arc::clone(&events));
+    ///
+    /// let mut buf: Buffer<isize> = Buffer::new(5);
+    /// buf.set_watermarks(1, 3);
+    /// buf.set_on_high(move |size| high_log.lock().unwrap().push(("high", size)));
+    /// buf.set_on_low(move |size| low_log.lock().unwrap().push(("low", size)));
+    ///
+    /// // Rising past the high watermark fires once
+    /// for i in 0..5 {
+    ///     buf.add(i);
+    /// }
+    /// assert_eq!(*events.lock().unwrap(), vec![("high", 3)]);
+    ///
+    /// // Dipping below the high watermark and back does not fire again
+    /// buf.remove();
+    /// buf.remove();
+    /// buf.remove();
+    /// buf.add(5);
+    /// assert_eq!(events.lock().unwrap().len(), 1);
+    ///
+    /// // Falling past the low watermark fires once
+    /// buf.remove();
+    /// buf.remove();
+    /// buf.remove();
+    /// assert_eq!(*events.lock().unwrap(), vec![("high", 3), ("low", 1)]);
+    ///
+    /// // Bulk operations are checked too
+    /// buf.replace_contents(vec![1, 2, 3, 4]).unwrap();
+    /// buf.drain();
+    /// assert_eq!(
+    ///     *events.lock().unwrap(),
+    ///     vec![("high", 3), ("low", 1), ("high", 4), ("low", 0)]
+    /// );
+    /// ```
+    pub fn set_watermarks(&mut self, low: usize, high: usize) {
+        assert!(low <= high, "the low watermark must not exceed the high watermark");
+        self.watermarks = Some(Watermarks {
+            low,
+            high,
+            filled: self.queue.len() >= high,
+        });
+    }
+
+    /// Register a callback to be invoked when the buffer fills up to its high
+    /// watermark
+    ///
+    /// The callback receives the size of the buffer. See `set_watermarks`
+    /// for when it fires. Registering a new callback replaces any existing
+    /// one.
+    ///
+    /// # Parameters
+    /// - `hook`: Callback to invoke on each high watermark crossing
+    pub fn set_on_high<F: FnMut(usize) + Send + 'static>(&mut self, hook: F) {
+        self.on_high = Some(Box::new(hook));
+    }
+
+    /// Register a callback to be invoked when the buffer drains down to its
+    /// low watermark
+    ///
+    /// The callback receives the size of the buffer. See `set_watermarks`
+    /// for when it fires. Registering a new callback replaces any existing
+    /// one.
+    ///
+    /// # Parameters
+    /// - `hook`: Callback to invoke on each low watermark crossing
+    pub fn set_on_low<F: FnMut(usize) + Send + 'static>(&mut self, hook: F) {
+        self.on_low = Some(Box::new(hook));
+    }
+
+    /// Fire the watermark callbacks if the size has crossed a watermark
+    ///
+    /// Called after every operation that changes the size of the buffer.
+    fn check_watermarks(&mut self) {
+        let size = self.queue.len();
+        let marks = match self.watermarks.as_mut() {
+            Some(marks) => marks,
+            None => return,
+        };
+
+        if !marks.filled && size >= marks.high {
+            marks.filled = true;
+            if let Some(hook) = self.on_high.as_mut() {
+                hook(size);
+            }
+        } else if marks.filled && size <= marks.low {
+            marks.filled = false;
+            if let Some(hook) = self.on_low.as_mut() {
+                hook(size);
+            }
+        }
+    }
+
+    /// Convert the buffer into a fixed-size array
+    ///
+    /// # Returns
+    /// - `Ok([T; N])`: The elements of the buffer, oldest first
+    /// - `Err(QueueError::SizeMismatch)`
+    ///
+    /// # Errors
+    /// Returns an error if the buffer does not hold exactly `N` elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.add(3);
+    /// assert_eq!(buf.try_into_array::<3>(), Ok([1, 2, 3]));
+    ///
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// assert_eq!(
+    ///     buf.try_into_array::<3>(),
+    ///     Err(QueueError::SizeMismatch { expected: 3, actual: 1 })
+    /// );
+    /// ```
+    pub fn try_into_array<const N: usize>(self) -> Result<[T; N], QueueError> {
+        Vec::from(self.queue)
+            .try_into()
+            .map_err(|queue: Vec<T>| QueueError::SizeMismatch {
+                expected: N,
+                actual: queue.len(),
+            })
+    }
+
+    /// Remove the oldest elements until the buffer holds `target` elements
+    ///
+    /// Nothing is removed if the buffer already holds `target` elements or
+    /// fewer.
+    ///
+    /// # Parameters
+    /// - `target`: Number of elements to leave in the buffer
+    ///
+    /// # Returns
+    /// The removed elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(5);
+    /// for i in 1..=5 {
+    ///     buf.add(i);
+    /// }
+    /// assert_eq!(buf.drain_to_size(10), vec![]);
+    /// assert_eq!(buf.drain_to_size(2), vec![1, 2, 3]);
+    /// assert_eq!(buf.peek(), Ok(4));
+    /// assert_eq!(buf.drain_to_size(0), vec![4, 5]);
+    /// assert_eq!(buf.size(), 0);
+    /// ```
+    pub fn drain_to_size(&mut self, target: usize) -> Vec<T> {
+        let excess = self.queue.len().saturating_sub(target);
+        let drained = self.queue.drain(..excess).collect();
+        self.check_watermarks();
+        drained
+    }
+
+    /// Fill the free slots of the buffer with generated values
+    ///
+    /// `f` is called once per free slot, and each result is added at the
+    /// tail of the buffer, leaving it full. Nothing happens if the buffer is
+    /// already full.
+    ///
+    /// # Parameters
+    /// - `f`: Function generating each padding value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(4);
+    /// buf.add(-1);
+    /// buf.add(-2);
+    ///
+    /// let mut counter = 0;
+    /// buf.fill_to_capacity_with(|| {
+    ///     counter += 1;
+    ///     counter
+    /// });
+    /// assert_eq!(buf.size(), 4);
+    /// assert_eq!(buf.add(5), Err("The buffer is full"));
+    ///
+    /// buf.remove();
+    /// buf.remove();
+    /// assert_eq!(buf.remove(), Ok(1));
+    /// assert_eq!(buf.remove(), Ok(2));
+    /// ```
+    pub fn fill_to_capacity_with<F: FnMut() -> T>(&mut self, f: F) {
+        let free = self.capacity.saturating_sub(self.queue.len());
+        self.queue.extend(std::iter::repeat_with(f).take(free));
+        keep_contiguous(&mut self.queue);
+        self.check_watermarks();
+    }
+
+    /// Borrow all elements of the buffer
+    ///
+    /// # Returns
+    /// A slice of the buffer's elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    ///
+    /// let mut total = 0;
+    /// for val in buf.peek_all() {
+    ///     total += val;
+    /// }
+    /// assert_eq!(total, 3);
+    /// assert_eq!(buf.peek_all(), &[1, 2]);
+    /// assert_eq!(buf.size(), 2);
+    /// ```
+    pub fn peek_all(&self) -> &[T] {
+        contiguous(&self.queue)
+    }
+
+    /// Borrow the elements of the buffer in chunks of `n`
+    ///
+    /// Mirrors `slice::as_chunks`, without cloning any elements.
+    ///
+    /// # Parameters
+    /// - `n`: Number of elements in each chunk
+    ///
+    /// # Returns
+    /// The chunks of exactly `n` elements, oldest first, and the remaining
+    /// newest elements that do not fill a chunk
+    ///
+    /// # Panics
+    /// Panics if `n` is zero
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(8);
+    /// for i in 1..=7 {
+    ///     buf.add(i);
+    /// }
+    ///
+    /// let (chunks, remainder) = buf.as_chunks(2);
+    /// assert_eq!(chunks, vec![&[1, 2][..], &[3, 4], &[5, 6]]);
+    /// assert_eq!(remainder, &[7]);
+    /// ```
+    pub fn as_chunks(&self, n: usize) -> (Vec<&[T]>, &[T]) {
+        assert!(n != 0, "chunk size must be non-zero");
+
+        let vals = contiguous(&self.queue);
+        let (chunked, remainder) = vals.split_at(vals.len() - vals.len() % n);
+        (chunked.chunks(n).collect(), remainder)
+    }
+
+    /// Replace all elements of the buffer
+    ///
+    /// The replacement is atomic: if the new elements do not fit within the
+    /// buffer's capacity, the buffer is left exactly as it was.
+    ///
+    /// # Parameters
+    /// - `vals`: Elements to fill the buffer with, oldest first
+    ///
+    /// # Returns
+    /// - `Ok(Vec<T>)`: The previous elements of the buffer, oldest first
+    /// - `Err(QueueError::Full)`
+    ///
+    /// # Errors
+    /// Returns an error if there are more new elements than the buffer's
+    /// capacity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    ///
+    /// assert_eq!(buf.replace_contents(vec![7, 8, 9]), Ok(vec![1, 2]));
+    /// assert_eq!(buf.size(), 3);
+    /// assert_eq!(buf.peek(), Ok(7));
+    ///
+    /// // Over-capacity replacements roll back
+    /// assert_eq!(buf.replace_contents(vec![1, 2, 3, 4]), Err(QueueError::Full));
+    /// assert_eq!(buf.peek_all(), &[7, 8, 9]);
+    /// ```
+    pub fn replace_contents<I: IntoIterator<Item = T>>(&mut self, vals: I) -> Result<Vec<T>, QueueError> {
+        let vals: Vec<T> = vals.into_iter().take(self.capacity.saturating_add(1)).collect();
+        if vals.len() > self.capacity {
+            return Err(QueueError::Full);
+        }
+        let replaced = std::mem::replace(&mut self.queue, vals.into()).into();
+        self.check_watermarks();
+        Ok(replaced)
+    }
+
+    /// Remove consecutive elements that are considered the same
+    ///
+    /// Forwards to `Vec::dedup_by`: `same` is called with each element and
+    /// the last element kept before it, and the element is removed if it
+    /// returns `true`. The first element of each run is kept, and the order
+    /// of the buffer is preserved.
+    ///
+    /// # Parameters
+    /// - `same`: Function deciding whether two elements are the same
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// #[derive(Clone)]
+    /// struct Reading {
+    ///     sensor_id: usize,
+    ///     value: f64,
+    /// }
+    ///
+    /// let mut buf: Buffer<Reading> = Buffer::new(3);
+    /// buf.add(Reading { sensor_id: 4, value: 1.5 });
+    /// buf.add(Reading { sensor_id: 4, value: 1.7 });
+    /// buf.add(Reading { sensor_id: 9, value: 0.2 });
+    ///
+    /// buf.dedup_by(|a, b| a.sensor_id == b.sensor_id);
+    /// assert_eq!(buf.size(), 2);
+    /// assert_eq!(buf.remove().unwrap().value, 1.5);
+    /// assert_eq!(buf.remove().unwrap().sensor_id, 9);
+    /// ```
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, same: F) {
+        let mut vals: Vec<T> = std::mem::take(&mut self.queue).into();
+        vals.dedup_by(same);
+        self.queue = vals.into();
+        self.check_watermarks();
+    }
+
+    /// Increase the capacity of the buffer
+    ///
+    /// # Parameters
+    /// - `n`: Number of slots to add to the capacity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(1);
+    /// buf.add(1);
+    /// assert_eq!(buf.add(2), Err("The buffer is full"));
+    ///
+    /// buf.grow_by(2);
+    /// assert_eq!(buf.capacity(), 3);
+    /// assert_eq!(buf.add(2), Ok(None));
+    /// ```
+    pub fn grow_by(&mut self, n: usize) {
+        self.capacity += n;
+    }
+
+    /// Decrease the capacity of the buffer
+    ///
+    /// No elements are ever removed: shrinking below the number of elements
+    /// in the buffer is an error, so the returned list of evicted elements is
+    /// always empty.
+    ///
+    /// # Parameters
+    /// - `n`: Number of slots to remove from the capacity
+    ///
+    /// # Returns
+    /// - `Ok(Vec<T>)`: The evicted elements, which is always empty
+    /// - `Err(QueueError::OutOfBounds)`
+    /// - `Err(QueueError::Full)`
+    ///
+    /// # Errors
+    /// Returns an error, leaving the capacity unchanged, if `n` is greater
+    /// than the capacity (`OutOfBounds`), or if the buffer holds more
+    /// elements than the decreased capacity would allow (`Full`)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(5);
+    /// buf.add(1);
+    /// buf.add(2);
+    ///
+    /// assert_eq!(buf.shrink_by(2), Ok(vec![]));
+    /// assert_eq!(buf.capacity(), 3);
+    ///
+    /// // Too few slots left for the elements
+    /// assert_eq!(buf.shrink_by(2), Err(QueueError::Full));
+    /// assert_eq!(buf.capacity(), 3);
+    ///
+    /// // More slots than the capacity
+    /// assert_eq!(buf.shrink_by(4), Err(QueueError::OutOfBounds));
+    /// assert_eq!(buf.capacity(), 3);
+    /// ```
+    pub fn shrink_by(&mut self, n: usize) -> Result<Vec<T>, QueueError> {
+        let capacity = self.capacity.checked_sub(n).ok_or(QueueError::OutOfBounds)?;
+        if capacity < self.queue.len() {
+            return Err(QueueError::Full);
+        }
+        self.capacity = capacity;
+        Ok(vec![])
+    }
+
+    /// Change the capacity of the buffer, reporting what changed
+    ///
+    /// If the buffer holds more elements than the new capacity allows, the
+    /// excess is evicted from the _newest_ end, so the elements closest to
+    /// removal are kept.
+    ///
+    /// # Parameters
+    /// - `new_capacity`: Capacity of the buffer after the resize
+    ///
+    /// # Returns
+    /// `Ok(ResizeOutcome<T>)`: The old and new capacities, and any evicted
+    /// elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    ///
+    /// // Grow
+    /// let outcome = buf.resize(5).unwrap();
+    /// assert_eq!((outcome.old_capacity, outcome.new_capacity), (3, 5));
+    /// assert!(outcome.evicted.is_empty());
+    ///
+    /// // Shrink, but not below the size
+    /// let outcome = buf.resize(2).unwrap();
+    /// assert_eq!((outcome.old_capacity, outcome.new_capacity), (5, 2));
+    /// assert!(outcome.evicted.is_empty());
+    ///
+    /// // Shrink below the size
+    /// buf.grow_by(2);
+    /// buf.add(3);
+    /// buf.add(4);
+    /// let outcome = buf.resize(1).unwrap();
+    /// assert_eq!(outcome.evicted, vec![2, 3, 4]);
+    /// assert_eq!(buf.capacity(), 1);
+    /// assert_eq!(buf.peek_all(), &[1]);
+    /// ```
+    pub fn resize(&mut self, new_capacity: usize) -> Result<ResizeOutcome<T>, QueueError> {
+        let old_capacity = self.capacity;
+        let evicted = self.queue.split_off(self.queue.len().min(new_capacity)).into();
+        self.capacity = new_capacity;
+        self.check_watermarks();
+        Ok(ResizeOutcome {
+            old_capacity,
+            new_capacity,
+            evicted,
+        })
+    }
+
+    /// Change the capacity of the buffer, evicting its oldest elements if
+    /// they no longer fit
+    ///
+    /// Unlike `Buffer::resize`, shrinking below the current `size` keeps the
+    /// newest elements, returning the evicted ones so nothing is silently
+    /// lost. A capacity of zero evicts every element.
+    ///
+    /// # Parameters
+    /// - `new_capacity`: Capacity of the buffer after the change
+    ///
+    /// # Returns
+    /// `Ok(Vec<T>)`: The evicted elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.add(3);
+    ///
+    /// // Grow
+    /// assert_eq!(buf.set_capacity(5), Ok(vec![]));
+    /// assert_eq!(buf.capacity(), 5);
+    ///
+    /// // Shrink to exactly the current size
+    /// assert_eq!(buf.set_capacity(3), Ok(vec![]));
+    /// assert!(buf.is_full());
+    ///
+    /// // Shrink below the current size
+    /// assert_eq!(buf.set_capacity(1), Ok(vec![1, 2]));
+    /// assert_eq!(buf.peek_all(), &[3]);
+    ///
+    /// // Shrink to zero
+    /// assert_eq!(buf.set_capacity(0), Ok(vec![3]));
+    /// assert_eq!(buf.size(), 0);
+    /// assert_eq!(buf.add(4), Err("The buffer is full"));
+    /// ```
+    pub fn set_capacity(&mut self, new_capacity: usize) -> Result<Vec<T>, QueueError> {
+        let excess = self.queue.len().saturating_sub(new_capacity);
+        let evicted = self.queue.drain(..excess).collect();
+        self.capacity = new_capacity;
+        self.check_watermarks();
+        Ok(evicted)
+    }
+
+    /// Check whether the buffer is full
+    ///
+    /// # Returns
+    /// `true` if the `size` of the buffer has reached its `capacity`, in which
+    /// case further additions will return an error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(2);
+    /// buf.add(1);
+    /// assert!(!buf.is_full());
+    /// buf.add(2);
+    /// assert!(buf.is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.queue.len() >= self.capacity
+    }
+
+    /// Remove all elements from the buffer
+    ///
+    /// The capacity of the buffer is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(2);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.clear();
+    /// assert_eq!(buf.size(), 0);
+    /// assert_eq!(buf.capacity(), 2);
+    /// ```
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.check_watermarks();
+    }
+
+    /// Remove all elements from the buffer and return them
+    ///
+    /// The capacity of the buffer is unchanged, so it can be refilled
+    /// straight away.
+    ///
+    /// # Returns
+    /// The removed elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(2);
+    /// buf.add(1);
+    /// buf.add(2);
+    ///
+    /// assert_eq!(buf.drain(), vec![1, 2]);
+    /// assert_eq!(buf.size(), 0);
+    /// assert_eq!(buf.capacity(), 2);
+    ///
+    /// assert_eq!(buf.add(3), Ok(None));
+    /// assert_eq!(buf.add(4), Ok(None));
+    /// assert_eq!(buf.add(5), Err("The buffer is full"));
+    /// ```
+    pub fn drain(&mut self) -> Vec<T> {
+        let vals = std::mem::take(&mut self.queue).into();
+        self.check_watermarks();
+        vals
+    }
+
+    /// Move all elements of the buffer into a reusable vector
+    ///
+    /// `out` is cleared first, then filled with the buffer's elements, oldest
+    /// first, leaving the buffer empty. Unlike `drain`, no new vector is
+    /// allocated, so a scratch vector can be reused for every flush.
+    ///
+    /// # Parameters
+    /// - `out`: Vector to fill with the buffer's elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(4);
+    /// let mut out: Vec<isize> = Vec::with_capacity(4);
+    /// let allocation = out.as_ptr();
+    ///
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.drain_into(&mut out);
+    /// assert_eq!(out, vec![1, 2]);
+    /// assert_eq!(buf.size(), 0);
+    ///
+    /// buf.add(3);
+    /// buf.drain_into(&mut out);
+    /// assert_eq!(out, vec![3]);
+    /// assert_eq!(out.as_ptr(), allocation);
+    /// ```
+    pub fn drain_into(&mut self, out: &mut Vec<T>) {
+        out.clear();
+        out.extend(self.queue.drain(..));
+        self.check_watermarks();
+    }
+
+    /// Release memory the buffer no longer needs
+    ///
+    /// The elements, their order and the `capacity` limit of the buffer are
+    /// unchanged; only unused memory is handed back to the allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(1000);
+    /// for i in 0..1000 {
+    ///     buf.add(i);
+    /// }
+    /// while buf.size() > 3 {
+    ///     buf.remove();
+    /// }
+    ///
+    /// buf.shrink_to_fit();
+    /// assert_eq!(buf.capacity(), 1000);
+    /// assert_eq!(buf.to_vec(), vec![997, 998, 999]);
+    ///
+    /// // Shrinking after removing from the head keeps the elements in one slice
+    /// let mut buf: Buffer<isize> = Buffer::new(16);
+    /// for i in 0..16 {
+    ///     buf.add(i);
+    /// }
+    /// for _ in 0..4 {
+    ///     buf.remove();
+    /// }
+    /// buf.shrink_to_fit();
+    /// assert_eq!(buf.as_slice().len(), buf.size());
+    /// assert_eq!(buf.as_slice(), &(4..16).collect::<Vec<_>>()[..]);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.queue.shrink_to_fit();
+        // Shrinking can wrap the elements around the end of the ring. They are
+        // rearranged in place, as `keep_contiguous` would grow the allocation
+        // straight back.
+        self.queue.make_contiguous();
+    }
+
+    /// Remove all elements from the buffer and return them
+    ///
+    /// Superseded by `Buffer::drain`, which this calls.
+    ///
+    /// # Returns
+    /// The removed elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![allow(deprecated)]
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(2);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// assert_eq!(buf.take_all(), vec![1, 2]);
+    /// assert_eq!(buf.size(), 0);
+    /// ```
+    #[deprecated(since = "1.1.0", note = "use `Buffer::drain` instead")]
+    pub fn take_all(&mut self) -> Vec<T> {
+        self.drain()
+    }
+
+    /// Iterate over clones of the elements in the buffer
+    ///
+    /// The buffer itself is left untouched.
+    ///
+    /// # Returns
+    /// An iterator over owned copies of the elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// assert_eq!(buf.iter_cloned().collect::<Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(buf.size(), 2);
+    /// ```
+    pub fn iter_cloned(&self) -> impl Iterator<Item = T> + '_ {
+        self.queue.iter().cloned()
+    }
+
+    /// Get a copy of the buffer's contents as a vector
+    ///
+    /// Unlike `into_iter`, the buffer itself is left untouched.
+    ///
+    /// # Returns
+    /// A vector of cloned elements, with the oldest at index 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// assert_eq!(buf.to_vec(), vec![1, 2]);
+    /// assert_eq!(buf.size(), 2);
+    /// ```
+    pub fn to_vec(&self) -> Vec<T> {
+        self.queue.iter().cloned().collect()
+    }
+
+    /// View the buffer's contents as a slice
+    ///
+    /// This avoids the allocation of `to_vec`. The slice is a live view that
+    /// borrows the buffer, so it cannot outlive the next mutation.
+    ///
+    /// # Returns
+    /// A slice of the elements, with the oldest at index 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.add(3);
+    /// assert_eq!(buf.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        contiguous(&self.queue)
+    }
+
+    /// Clone the leading elements of the buffer while they match a predicate
+    ///
+    /// This is the read-only counterpart of `drain_front_while`: the buffer
+    /// is left unchanged.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against, oldest first
+    ///
+    /// # Returns
+    /// Clones of the leading matching elements, oldest first, stopping at the
+    /// first element that does not match
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(4);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.add(5);
+    /// buf.add(3);
+    /// assert_eq!(buf.take_while(|&x| x < 4), vec![1, 2]);
+    /// assert_eq!(buf.size(), 4);
+    /// ```
+    pub fn take_while<F: FnMut(&T) -> bool>(&self, mut pred: F) -> Vec<T> {
+        self.queue.iter().take_while(|val| pred(val)).cloned().collect()
+    }
+
+    /// Get a reference to the element at a position in the buffer
+    ///
+    /// Position `0` is the head of the buffer, the element `peek` returns.
+    /// The buffer is left unchanged.
+    ///
+    /// # Parameters
+    /// - `index`: Oldest-first position of the element
+    ///
+    /// # Returns
+    /// - `Some(&T)`: The element at `index`
+    /// - `None`: If `index` is out of bounds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// assert_eq!(buf.get(0), Some(&buf.peek().unwrap()));
+    /// assert_eq!(buf.get(1), Some(&2));
+    /// assert_eq!(buf.get(2), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.queue.get(index)
+    }
+
+    /// Remove all elements matching a predicate from the buffer
+    ///
+    /// This is the counterpart of keeping only the matching elements: the
+    /// matches are removed and returned, and the order of the remaining
+    /// elements is preserved. The capacity taken up by
+    /// the removed elements is freed for new additions.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against
+    ///
+    /// # Returns
+    /// The removed elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(6);
+    /// for i in 1..=6 {
+    ///     buf.add(i);
+    /// }
+    ///
+    /// assert_eq!(buf.remove_matching(|&x| x % 2 == 1), vec![1, 3, 5]);
+    /// assert_eq!(buf.peek_all(), &[2, 4, 6]);
+    /// assert_eq!(buf.add(7), Ok(None));
+    /// ```
+    pub fn remove_matching<F: FnMut(&T) -> bool>(&mut self, pred: F) -> Vec<T> {
+        let (removed, kept): (Vec<T>, Vec<T>) = std::mem::take(&mut self.queue).into_iter().partition(pred);
+        self.queue = kept.into();
+        self.check_watermarks();
+        removed
+    }
+
+    /// Keep only the elements of the buffer matching a predicate
+    ///
+    /// The order of the kept elements is preserved. Unlike `remove_matching`,
+    /// the dropped elements are not returned.
+    ///
+    /// # Parameters
+    /// - `f`: Predicate to test each element against
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(5);
+    /// for i in 1..=5 {
+    ///     buf.add(i);
+    /// }
+    ///
+    /// // Drop from the head, the middle and the tail
+    /// buf.retain(|&x| x != 1 && x != 3 && x != 5);
+    /// assert_eq!(buf.to_vec(), vec![2, 4]);
+    /// assert_eq!(buf.peek(), Ok(2));
+    /// assert_eq!(buf.capacity(), 5);
+    /// ```
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        self.queue.retain(f);
+        self.check_watermarks();
+    }
+
+    /// Iterate over the elements of the buffer
+    ///
+    /// The buffer is borrowed, not consumed or modified.
+    ///
+    /// # Returns
+    /// An iterator over references to the elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// assert_eq!(buf.iter().collect::<Vec<_>>(), vec![&1, &2]);
+    /// assert_eq!(buf.size(), 2);
+    /// assert_eq!(buf.peek(), Ok(1));
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        contiguous(&self.queue).iter()
+    }
+
+    /// Reserve free slots in the buffer for a multi-step addition
+    ///
+    /// The returned guard mutably borrows the buffer, so the reserved slots
+    /// cannot be taken by any other addition while it is alive. Elements are
+    /// added through `SlotReservation::commit`, and any slots left unused are
+    /// released when the guard is dropped.
+    ///
+    /// # Parameters
+    /// - `n`: Number of slots to reserve
+    ///
+    /// # Returns
+    /// - `Ok(SlotReservation<T>)`: A guard over the reserved slots
+    /// - `Err(QueueError::Full)`
+    ///
+    /// # Errors
+    /// Returns an error if the buffer has fewer than `n` free slots
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(4);
+    /// buf.add(1);
+    /// assert!(buf.reserve_slots(4).is_err());
+    ///
+    /// {
+    ///     let mut slots = buf.reserve_slots(3).unwrap();
+    ///     assert_eq!(slots.remaining(), 3);
+    ///     assert_eq!(slots.commit(2), Ok(()));
+    ///     assert_eq!(slots.remaining(), 2);
+    /// }
+    ///
+    /// // Unused slots are released
+    /// assert_eq!(buf.size(), 2);
+    /// assert_eq!(buf.add(3), Ok(None));
+    /// assert_eq!(buf.add(4), Ok(None));
+    /// assert_eq!(buf.add(5), Err("The buffer is full"));
+    /// ```
+    pub fn reserve_slots(&mut self, n: usize) -> Result<SlotReservation<'_, T>, QueueError> {
+        if self.capacity.saturating_sub(self.queue.len()) < n {
+            return Err(QueueError::Full);
+        }
+        Ok(SlotReservation {
+            buffer: self,
+            remaining: n,
+        })
+    }
+
+    /// Add elements to the buffer until it is full
+    ///
+    /// Elements are only taken from `items` while the buffer has room for
+    /// them, so none are lost: when the buffer fills up, any elements not yet
+    /// added are left in the iterator. Pass an iterator by reference (with
+    /// `Iterator::by_ref`) to keep hold of them.
+    ///
+    /// # Parameters
+    /// - `items`: Elements to add to the buffer
+    ///
+    /// # Returns
+    /// - `Ok(usize)`: The number of elements added
+    /// - `Err(QueueError::Full)`
+    ///
+    /// # Errors
+    /// Returns an error, without taking any elements, if the buffer is
+    /// already full
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// // All items fit
+    /// let mut buf: Buffer<isize> = Buffer::new(4);
+    /// assert_eq!(buf.add_all(vec![1, 2]), Ok(2));
+    ///
+    /// // Some items fit
+    /// let mut items = vec![3, 4, 5].into_iter();
+    /// assert_eq!(buf.add_all(items.by_ref()), Ok(2));
+    /// assert_eq!(items.collect::<Vec<_>>(), vec![5]);
+    ///
+    /// // The buffer is already full
+    /// assert_eq!(buf.add_all(vec![6]), Err(QueueError::Full));
+    /// assert_eq!(buf.peek_all(), &[1, 2, 3, 4]);
+    /// ```
+    pub fn add_all<I: IntoIterator<Item = T>>(&mut self, items: I) -> Result<usize, QueueError> {
+        if self.is_full() {
+            return Err(QueueError::Full);
+        }
+
+        let mut items = items.into_iter();
+        let mut added = 0;
+        while !self.is_full() {
+            match items.next() {
+                Some(val) => {
+                    let _ = self.add(val);
+                    added += 1;
+                }
+                None => break,
+            }
+        }
+        Ok(added)
+    }
+
+    /// Top the buffer up to capacity from `source`
+    ///
+    /// This is the infallible counterpart of `add_all`: a full buffer simply
+    /// takes nothing. Only as many elements as fit are pulled from `source`,
+    /// so pass an iterator by reference (with `Iterator::by_ref`) to keep
+    /// hold of the rest.
+    ///
+    /// # Parameters
+    /// - `source`: Elements to add to the buffer
+    ///
+    /// # Returns
+    /// The number of elements added
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(5);
+    ///
+    /// // A source smaller than the remaining capacity
+    /// assert_eq!(buf.top_up_from(vec![1, 2]), 2);
+    ///
+    /// // A source equal to the remaining capacity
+    /// assert_eq!(buf.top_up_from(vec![3, 4, 5]), 3);
+    /// assert!(buf.is_full());
+    ///
+    /// // A source larger than the remaining capacity
+    /// buf.remove();
+    /// let mut source = vec![6, 7, 8].into_iter();
+    /// assert_eq!(buf.top_up_from(source.by_ref()), 1);
+    /// assert_eq!(source.collect::<Vec<_>>(), vec![7, 8]);
+    /// assert_eq!(buf.peek_all(), &[2, 3, 4, 5, 6]);
+    /// ```
+    pub fn top_up_from<I: IntoIterator<Item = T>>(&mut self, source: I) -> usize {
+        self.add_all(source).unwrap_or(0)
+    }
+
+    /// Move as many of another buffer's elements into this one as fit
+    ///
+    /// Elements are moved oldest first, and any that do not fit are left in
+    /// `other`, so none are lost. Watermark callbacks fire on both buffers as
+    /// elements move between them.
+    ///
+    /// # Parameters
+    /// - `other`: Buffer to take elements from
+    ///
+    /// # Returns
+    /// `Ok(usize)`: The number of elements moved, which is `0` if this buffer
+    /// has no room
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(4);
+    /// let mut other: Buffer<isize> = Buffer::new(4);
+    ///
+    /// // All of `other` fits
+    /// other.add(1);
+    /// other.add(2);
+    /// assert_eq!(buf.merge_from(&mut other), Ok(2));
+    /// assert_eq!(other.size(), 0);
+    ///
+    /// // Some of `other` fits
+    /// other.add(3);
+    /// other.add(4);
+    /// other.add(5);
+    /// assert_eq!(buf.merge_from(&mut other), Ok(2));
+    /// assert_eq!(buf.peek_all(), &[1, 2, 3, 4]);
+    /// assert_eq!(other.peek_all(), &[5]);
+    ///
+    /// // None of `other` fits
+    /// assert_eq!(buf.merge_from(&mut other), Ok(0));
+    /// assert_eq!(other.peek_all(), &[5]);
+    /// ```
+    pub fn merge_from(&mut self, other: &mut Buffer<T>) -> Result<usize, QueueError> {
+        let count = self.capacity.saturating_sub(self.queue.len()).min(other.queue.len());
+        for _ in 0..count {
+            if let Ok(val) = other.remove() {
+                let _ = self.add(val);
+            }
+        }
+        Ok(count)
+    }
+
+    /// Peek at the tail of the buffer
+    ///
+    /// The tail is the most recently added element, the last one scheduled
+    /// for removal.
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The newest element in the buffer
+    /// - `Err(QueueError::Empty)`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to peek into an empty buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// assert_eq!(buf.peek_back(), Err(QueueError::Empty));
+    ///
+    /// buf.add(1);
+    /// buf.add(2);
+    /// assert_eq!(buf.peek(), Ok(1));
+    /// assert_eq!(buf.peek_back(), Ok(2));
+    /// ```
+    pub fn peek_back(&self) -> Result<T, QueueError> {
+        self.queue.back().cloned().ok_or(QueueError::Empty)
+    }
+
+    /// Adds an element to the head of the buffer
+    ///
+    /// The element becomes the next one scheduled for removal.
+    ///
+    /// # Parameters
+    /// - `val`: Value to add to the buffer
+    ///
+    /// # Returns
+    /// - `Ok(None)`: Element addition was successful
+    /// - `Err(QueueError::Full)`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to add an element to a full
+    /// buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(2);
+    /// buf.add(1);
+    /// assert_eq!(buf.add_front(2), Ok(None));
+    /// assert_eq!(buf.peek(), Ok(2));
+    ///
+    /// assert_eq!(buf.add_front(3), Err(QueueError::Full));
+    /// assert_eq!(buf.peek_all(), &[2, 1]);
+    /// ```
+    pub fn add_front(&mut self, val: T) -> Result<Option<T>, QueueError> {
+        if self.is_full() {
+            return Err(QueueError::Full);
+        }
+        self.queue.push_front(val);
+        keep_contiguous(&mut self.queue);
+        self.check_watermarks();
+        Ok(None)
+    }
+}
+
+impl<T: Clone + PartialOrd> Buffer<T> {
+    /// Check whether the buffer is monotonically increasing
+    ///
+    /// Elements are compared from oldest to newest. An empty or
+    /// single-element buffer is trivially monotonic.
+    ///
+    /// # Returns
+    /// `true` if every element is greater than or equal to the one before it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// assert!(buf.is_monotonic_increasing());
+    /// buf.add(1);
+    /// buf.add(4);
+    /// assert!(buf.is_monotonic_increasing());
+    /// buf.add(2);
+    /// assert!(!buf.is_monotonic_increasing());
+    /// ```
+    pub fn is_monotonic_increasing(&self) -> bool {
+        self.queue.iter().zip(self.queue.iter().skip(1)).all(|(a, b)| a <= b)
+    }
+
+    /// Check whether the buffer is monotonically decreasing
+    ///
+    /// Elements are compared from oldest to newest. An empty or
+    /// single-element buffer is trivially monotonic.
+    ///
+    /// # Returns
+    /// `true` if every element is less than or equal to the one before it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(4);
+    /// assert!(buf.is_monotonic_decreasing());
+    /// buf.add(1);
+    /// assert!(buf.is_monotonic_decreasing());
+    /// buf.add(2);
+    /// assert!(!buf.is_monotonic_decreasing());
+    /// ```
+    pub fn is_monotonic_decreasing(&self) -> bool {
+        self.queue.iter().zip(self.queue.iter().skip(1)).all(|(a, b)| a >= b)
+    }
+
+    /// Get the rank of a value among the elements of the buffer
+    ///
+    /// The rank is the number of elements strictly less than the value, which
+    /// is the position it would take if inserted into a sorted copy of the
+    /// buffer. The buffer does not need to be sorted.
+    ///
+    /// # Parameters
+    /// - `val`: Value to rank
+    ///
+    /// # Returns
+    /// The number of elements less than `val`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(4);
+    /// buf.add(1);
+    /// buf.add(9);
+    /// assert_eq!(buf.rank(&5), 2);
+    /// assert_eq!(buf.rank(&10), 3);
+    /// ```
+    pub fn rank(&self, val: &T) -> usize {
+        self.queue.iter().filter(|&el| el < val).count()
+    }
+}
+
+impl<T: Clone + Eq + Hash> Buffer<T> {
+    /// Count the occurrences of each distinct element in the buffer
+    ///
+    /// # Returns
+    /// A map from each distinct element to the number of times it occurs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<char> = Buffer::new(3);
+    /// buf.add('a');
+    /// buf.add('b');
+    /// buf.add('a');
+    /// let counts = buf.tally();
+    /// assert_eq!(counts.len(), 2);
+    /// assert_eq!(counts[&'a'], 2);
+    /// assert_eq!(counts[&'b'], 1);
+    /// ```
+    pub fn tally(&self) -> HashMap<T, usize> {
+        let mut counts = HashMap::new();
+        for val in &self.queue {
+            *counts.entry(val.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+impl<T: Clone + PartialEq> Buffer<T> {
+    /// Check whether the buffer contains a value
+    ///
+    /// # Parameters
+    /// - `val`: Value to search for
+    ///
+    /// # Returns
+    /// `true` if any element of the buffer is equal to `val`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// assert!(buf.contains(&2));
+    /// assert!(!buf.contains(&4));
+    /// ```
+    pub fn contains(&self, val: &T) -> bool {
+        self.queue.contains(val)
+    }
+}
+
+impl<T: Clone> IsQueue<T> for Buffer<T> {
+    /// Adds an element to a buffer
+    ///
+    /// # Parameters
+    /// - `val`: Value to add to the buffer
+    ///
+    /// # Returns
+    /// - `Ok(None)`: Element addition was successful
+    /// - `Error`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to add an element to a full
+    /// buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use queues::*;
+    ///
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// assert_eq!(buf.add(42), Ok(None));
+    /// ```
+    fn add(&mut self, val: T) -> Result<Option<T>, &str> {
+        if self.queue.len() < self.capacity {
+            self.queue.push_back(val);
+            keep_contiguous(&mut self.queue);
+            self.check_watermarks();
+            Ok(None)
+        } else {
+            Err("The buffer is full")
+        }
+    }
+
+    /// Removes an element from the buffer and returns it.
+    ///
+    /// Removal takes amortized constant time, regardless of the capacity of
+    /// the buffer: the buffer keeps its elements in a single slice, which
+    /// occasionally needs an O(n) rearrangement.
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The oldest element in the buffer
+    /// - `Error`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to remove an element from
+    /// an empty buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(42);
+    /// assert_eq!(buf.remove(), Ok(42));
+    /// assert_eq!(buf.size(), 0);
+    ///
+    /// // Elements cycling through a full buffer stay viewable as one slice
+    /// let mut buf: Buffer<usize> = Buffer::new(3);
+    /// for i in 0..1000 {
+    ///     buf.add(i);
+    ///     if buf.is_full() {
+    ///         assert_eq!(buf.peek_all(), &[i - 2, i - 1, i]);
+    ///         assert_eq!(buf.remove(), Ok(i - 2));
+    ///     }
+    /// }
+    /// ```
+    fn remove(&mut self) -> Result<T, &str> {
+        match self.queue.pop_front() {
+            Some(val) => {
+                self.check_watermarks();
+                Ok(val)
+            }
+            None => Err("The buffer is empty"),
+        }
+    }
+
+    /// Peek at the head of the buffer
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The next element scheduled for removal from the buffer
+    /// - `Error`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to peek into an empty buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(42);
+    /// assert_eq!(buf.peek(), Ok(42));
+    /// ```
+    fn peek(&self) -> Result<T, &str> {
+        match self.queue.front() {
+            Some(val) => Ok(val.clone()),
+            None => Err("The buffer is empty"),
+        }
+    }
+
+    /// Gets the size of the buffer
+    ///
+    /// # Returns
+    /// The number of elements in the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// assert_eq!(buf.size(), 0);
+    /// buf.add(42);
+    /// assert_eq!(buf.size(), 1);
+    /// ```
+    fn size(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Gets the behavior of the buffer when an element is added at capacity
+    ///
+    /// # Returns
+    /// `OverflowBehavior::Reject`, as additions to a full buffer return an
+    /// error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let buf: Buffer<isize> = Buffer::new(3);
+    /// assert_eq!(buf.overflow_behavior(), OverflowBehavior::Reject);
+    /// ```
+    fn overflow_behavior(&self) -> OverflowBehavior {
+        OverflowBehavior::Reject
+    }
+}
+
+impl<T: Clone> IntoIterator for Buffer<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    /// Consumes the buffer, iterating over its elements
+    ///
+    /// # Returns
+    /// An iterator over the elements of the buffer, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.add(3);
+    /// assert_eq!(buf.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        Vec::from(self.queue).into_iter()
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq for Buffer<T> {
+    /// Compares two buffers for equality
+    ///
+    /// Two buffers are equal when they have the same `capacity` and hold
+    /// equal elements in the same FIFO order. Watermarks and their callbacks
+    /// are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut small: Buffer<isize> = Buffer::new(2);
+    /// let mut large: Buffer<isize> = Buffer::new(3);
+    /// small.add(1);
+    /// large.add(1);
+    /// assert_ne!(small, large);
+    ///
+    /// let mut other: Buffer<isize> = Buffer::new(2);
+    /// other.add(1);
+    /// assert_eq!(small, other);
+    /// ```
+    fn eq(&self, other: &Buffer<T>) -> bool {
+        self.capacity == other.capacity && self.queue == other.queue
+    }
+}
+
+impl<T: Clone + Eq> Eq for Buffer<T> {}
+
+impl<T: Clone> std::ops::Index<usize> for Buffer<T> {
+    type Output = T;
+
+    /// Gets a reference to the element at a position in the buffer
+    ///
+    /// Position `0` is the head of the buffer, the element `peek` returns.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.add(3);
+    /// assert_eq!(buf[0], buf.peek().unwrap());
+    /// assert_eq!(buf[2], 3);
+    /// ```
+    ///
+    /// ```should_panic
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(3);
+    /// buf.add(1);
+    /// buf.add(2);
+    /// buf.add(3);
+    /// let _ = buf[3];
+    /// ```
+    fn index(&self, index: usize) -> &T {
+        let size = self.queue.len();
+        self.queue
+            .get(index)
+            .unwrap_or_else(|| panic!("index out of bounds: the size is {} but the index is {}", size, index))
+    }
+}
+
+/// A reservation of free slots in a `Buffer<T>`
+///
+/// Created by `Buffer::reserve_slots`. Unused slots are released when the
+/// reservation is dropped.
+#[derive(Debug)]
+pub struct SlotReservation<'a, T: Clone> {
+    buffer: &'a mut Buffer<T>,
+    remaining: usize,
+}
+
+impl<T: Clone> SlotReservation<'_, T> {
+    /// Add an element to the buffer, using up one of the reserved slots
+    ///
+    /// # Parameters
+    /// - `val`: Value to add to the buffer
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the element was added
+    /// - `Err(QueueError::Full)`
+    ///
+    /// # Errors
+    /// Returns an error if all of the reserved slots have been used
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut buf: Buffer<isize> = Buffer::new(4);
+    /// let mut slots = buf.reserve_slots(1).unwrap();
+    /// assert_eq!(slots.commit(1), Ok(()));
+    /// assert_eq!(slots.commit(2), Err(QueueError::Full));
+    /// ```
+    pub fn commit(&mut self, val: T) -> Result<(), QueueError> {
+        if self.remaining == 0 {
+            return Err(QueueError::Full);
+        }
+        self.remaining -= 1;
+        let _ = self.buffer.add(val);
+        Ok(())
+    }
+
+    /// Gets the number of reserved slots that have not been used
+    ///
+    /// # Returns
+    /// The number of elements that can still be committed
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+/// The outcome of resizing a `Buffer<T>`
+///
+/// Returned by `Buffer::resize`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResizeOutcome<T> {
+    /// Capacity of the buffer before the resize
+    pub old_capacity: usize,
+    /// Capacity of the buffer after the resize
+    pub new_capacity: usize,
+    /// Elements evicted to fit the new capacity, oldest first
+    pub evicted: Vec<T>,
+}
+
+/// Represents a FIFO `CircularBuffer<T>` data structure.
+///
+/// This structure is a limited capacity queue, with optional provisions
+/// for default values. Under normal circumstances, the `size` of the
+/// queue grows until it reaches its `capacity`, at which point any
+/// further additions push out its oldest member.
+///
+/// If default values are specified, then the `size` of the queue
+/// is always equal to its `capacity`, with empty slots occupied by the
+/// specified default value.
+///
+/// # Type parameters
+/// - `T`: Any type that implements the `Clone` trait.
+///
+/// # Examples
+///
+/// ```
+/// # use queues::*;
+/// # fn main() {
+/// let mut cbuf = CircularBuffer::<isize>::new(3);
+/// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+///
+/// // Check sizes
+/// assert_eq!(cbuf.size(), 0);
+/// assert_eq!(cbuf_def.size(), 3);
+///
+/// // Add elements
+/// cbuf.add(6);
+/// cbuf_def.add(7);
+///
+/// // Peek at the next element scheduled for removal
+/// assert_eq!(cbuf.peek().unwrap(), 6);
+/// assert_eq!(cbuf_def.peek().unwrap(), 0);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CircularBuffer<T: Clone> {
+    queue: VecDeque<T>,
+    capacity: usize,
+    default_value: Option<T>,
+    // Number of elements that have entered the buffer since the newest
+    // genuinely-added element, or `None` if no added element remains. Always
+    // less than the capacity.
+    newest_age: Option<usize>,
+}
+
+impl<T: Clone> CircularBuffer<T> {
+    /// Default `CircularBuffer<T>` initializer
+    ///
+    /// # Returns
+    /// A new, empty `CircularBuffer<T>`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// assert_eq!(cbuf.size(), 0);
+    /// assert_eq!(cbuf.capacity(), 3);
+    /// ```
+    pub fn new(capacity: usize) -> CircularBuffer<T> {
+        CircularBuffer {
+            queue: VecDeque::new(),
+            capacity,
+            default_value: None,
+            newest_age: None,
+        }
+    }
+
+    /// Create a `CircularBuffer<T>` with default values
+    ///
+    /// # Returns
+    /// A new `CircularBuffer<T>` filled with default values
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let cbuf_def = CircularBuffer::with_default(3, -1isize);
+    /// assert_eq!(cbuf_def.size(), 3);
+    /// assert_eq!(cbuf_def.capacity(), 3);
+    /// assert_eq!(cbuf_def.peek(), Ok(-1));
+    /// ```
+    pub fn with_default(capacity: usize, default_value: T) -> CircularBuffer<T> {
+        let queue = std::iter::repeat_n(default_value.clone(), capacity).collect();
+
+        CircularBuffer {
+            queue,
+            capacity,
+            default_value: Some(default_value),
+            newest_age: None,
+        }
+    }
+
+    /// Create a `CircularBuffer<T>` pre-loaded with the tail of a slice
+    ///
+    /// The buffer holds the last `min(capacity, slice.len())` elements of the
+    /// slice, in order, so that the newest elements are kept when the slice
+    /// is longer than the buffer.
+    ///
+    /// # Parameters
+    /// - `capacity`: Capacity of the buffer
+    /// - `slice`: Elements to load into the buffer
+    ///
+    /// # Returns
+    /// A new `CircularBuffer<T>` holding the tail of the slice
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf = CircularBuffer::from_slice_tail(3, &[1isize, 2, 3, 4, 5]);
+    /// assert_eq!(cbuf.capacity(), 3);
+    /// assert_eq!(cbuf.size(), 3);
+    /// assert_eq!(cbuf.remove(), Ok(3));
+    ///
+    /// let cbuf = CircularBuffer::from_slice_tail(3, &[1isize]);
+    /// assert_eq!(cbuf.capacity(), 3);
+    /// assert_eq!(cbuf.size(), 1);
+    /// assert_eq!(cbuf.peek(), Ok(1));
+    /// ```
+    pub fn from_slice_tail(capacity: usize, slice: &[T]) -> CircularBuffer<T> {
+        CircularBuffer {
+            queue: slice[slice.len().saturating_sub(capacity)..].iter().cloned().collect(),
+            capacity,
+            default_value: None,
+            newest_age: None,
+        }
+    }
+
+    /// Gets the capacity of the `CircularBuffer<T>`
+    ///
+    /// # Returns
+    /// The number of allowed elements in the buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::CircularBuffer;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// assert_eq!(cbuf.capacity(), 3);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Create a resized copy of the `CircularBuffer<T>`
+    ///
+    /// The original buffer is left untouched. The copy holds the newest
+    /// `min(size, new_capacity)` elements of the original. For buffers with
+    /// default values, growing fills the front of the copy with the default
+    /// value so that its `size` remains equal to its `capacity`.
+    ///
+    /// # Parameters
+    /// - `new_capacity`: Capacity of the returned buffer
+    ///
+    /// # Returns
+    /// A new `CircularBuffer<T>` with the given capacity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    ///
+    /// // Grow
+    /// let mut grown = cbuf.clone_resized(5);
+    /// assert_eq!(grown.capacity(), 5);
+    /// assert_eq!(grown.size(), 3);
+    /// assert_eq!(grown.add(4), Ok(None));
+    ///
+    /// // Shrink, keeping the newest elements
+    /// let mut shrunk = cbuf.clone_resized(2);
+    /// assert_eq!(shrunk.capacity(), 2);
+    /// assert_eq!(shrunk.remove(), Ok(2));
+    /// assert_eq!(shrunk.remove(), Ok(3));
+    ///
+    /// // Equal size
+    /// let same = cbuf.clone_resized(3);
+    /// assert_eq!(same.capacity(), 3);
+    /// assert_eq!(same.peek(), Ok(1));
+    ///
+    /// // The original is unchanged
+    /// assert_eq!(cbuf.capacity(), 3);
+    /// assert_eq!(cbuf.size(), 3);
+    /// assert_eq!(cbuf.peek(), Ok(1));
+    ///
+    /// // Growing a buffer with default values fills the front
+    /// let mut cbuf_def = CircularBuffer::with_default(2, 0isize);
+    /// cbuf_def.add(7);
+    /// let grown_def = cbuf_def.clone_resized(4);
+    /// assert_eq!(grown_def.size(), 4);
+    /// assert_eq!(grown_def.peek(), Ok(0));
+    /// ```
+    pub fn clone_resized(&self, new_capacity: usize) -> CircularBuffer<T> {
+        let size = self.queue.len();
+        let mut queue = match &self.default_value {
+            Some(val) => std::iter::repeat_n(val.clone(), new_capacity.saturating_sub(size)).collect(),
+            None => VecDeque::with_capacity(new_capacity),
+        };
+        queue.extend(self.queue.iter().skip(size.saturating_sub(new_capacity)).cloned());
+
+        CircularBuffer {
+            queue,
+            capacity: new_capacity,
+            default_value: self.default_value.clone(),
+            newest_age: self.newest_age.filter(|&age| age < new_capacity),
+        }
+    }
+
+    /// Change the capacity of the circular buffer, keeping its newest
+    /// elements
+    ///
+    /// This is the in-place counterpart of `clone_resized`. Shrinking drops
+    /// the oldest elements so that only the newest `new_capacity` remain.
+    /// Growing raises the limit; for buffers with default values, the new
+    /// slots are filled with the default value at the oldest end, so that
+    /// `size` stays equal to `capacity`.
+    ///
+    /// # Parameters
+    /// - `new_capacity`: Capacity of the circular buffer after the resize
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    ///
+    /// // Shrink, keeping the newest elements
+    /// cbuf.resize(2);
+    /// assert_eq!(cbuf.capacity(), 2);
+    /// assert_eq!(cbuf.to_vec(), vec![2, 3]);
+    ///
+    /// // Grow
+    /// cbuf.resize(4);
+    /// assert_eq!(cbuf.capacity(), 4);
+    /// assert_eq!(cbuf.size(), 2);
+    /// assert_eq!(cbuf.add(4), Ok(None));
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(1);
+    /// cbuf_def.add(2);
+    ///
+    /// // Shrink a buffer with default values
+    /// cbuf_def.resize(1);
+    /// assert_eq!(cbuf_def.to_vec(), vec![2]);
+    ///
+    /// // Grow a buffer with default values
+    /// cbuf_def.resize(3);
+    /// assert_eq!(cbuf_def.size(), cbuf_def.capacity());
+    /// assert_eq!(cbuf_def.to_vec(), vec![0, 0, 2]);
+    /// ```
+    pub fn resize(&mut self, new_capacity: usize) {
+        let excess = self.queue.len().saturating_sub(new_capacity);
+        self.queue.drain(..excess);
+        if let Some(val) = &self.default_value {
+            while self.queue.len() < new_capacity {
+                self.queue.push_front(val.clone());
+            }
+            keep_contiguous(&mut self.queue);
+        }
+        self.capacity = new_capacity;
+        self.set_newest_age(self.newest_age);
+    }
+
+    /// Find the first element in the circular buffer matching a predicate
+    ///
+    /// Elements are searched from oldest to newest. For buffers with default
+    /// values, the default-filled slots are included in the search.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against
+    ///
+    /// # Returns
+    /// - `Some(&T)`: A reference to the first matching element
+    /// - `None`: If no element matches
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(4);
+    /// assert_eq!(cbuf_def.find(|&x| x > 2), Some(&4));
+    /// assert_eq!(cbuf_def.find(|&x| x == 0), Some(&0));
+    /// assert_eq!(cbuf_def.find(|&x| x < 0), None);
+    /// ```
+    pub fn find<F: FnMut(&T) -> bool>(&self, mut pred: F) -> Option<&T> {
+        self.queue.iter().find(|val| pred(val))
+    }
+
+    /// Convert the circular buffer into a `Buffer<T>`
+    ///
+    /// The buffer shares the capacity and contents of this circular buffer,
+    /// in the same FIFO order. Any default-filled slots are carried over as
+    /// regular elements, and the default value itself is discarded.
+    ///
+    /// # Returns
+    /// A new `Buffer<T>` holding the circular buffer's elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    ///
+    /// let mut buf = cbuf.into_buffer();
+    /// assert_eq!(buf.size(), 3);
+    /// assert_eq!(buf.add(4), Err("The buffer is full"));
+    ///
+    /// let cbuf = buf.into_circular();
+    /// assert_eq!(cbuf.capacity(), 3);
+    /// assert_eq!(cbuf.peek(), Ok(1));
+    /// ```
+    pub fn into_buffer(self) -> Buffer<T> {
+        Buffer {
+            queue: self.queue,
+            ..Buffer::new(self.capacity)
+        }
+    }
+
+    /// Borrow the head of the circular buffer
+    ///
+    /// Unlike `peek`, this neither clones the element nor returns an error.
+    ///
+    /// # Returns
+    /// - `Some(&T)`: A reference to the next element scheduled for removal
+    /// - `None`: If the circular buffer is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<String> = CircularBuffer::new(2);
+    /// assert!(cbuf.try_peek_ref().is_none());
+    ///
+    /// let cbuf_def = CircularBuffer::with_default(2, String::from("none"));
+    /// assert_eq!(cbuf_def.try_peek_ref().map(|head| head.as_str()), Some("none"));
+    /// ```
+    pub fn try_peek_ref(&self) -> Option<&T> {
+        self.queue.front()
+    }
+
+    /// Check whether the circular buffer holds any genuinely-added elements
+    ///
+    /// For buffers without default values, this is the case whenever the
+    /// buffer is not empty. Buffers with default values are never empty, so
+    /// this instead checks whether any element added to the buffer has not
+    /// yet been displaced by default values refilled on `remove`.
+    ///
+    /// # Returns
+    /// `true` if the buffer holds at least one added element
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf_def = CircularBuffer::with_default(2, 0isize);
+    /// assert!(!cbuf_def.has_real_data());
+    ///
+    /// cbuf_def.add(5);
+    /// assert!(cbuf_def.has_real_data());
+    ///
+    /// // Removing a default keeps the added element
+    /// assert_eq!(cbuf_def.remove(), Ok(0));
+    /// assert!(cbuf_def.has_real_data());
+    ///
+    /// // Removing the added element leaves only defaults
+    /// assert_eq!(cbuf_def.remove(), Ok(5));
+    /// assert!(!cbuf_def.has_real_data());
+    ///
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(2);
+    /// assert!(!cbuf.has_real_data());
+    /// cbuf.add(5);
+    /// assert!(cbuf.has_real_data());
+    /// ```
+    pub fn has_real_data(&self) -> bool {
+        match self.default_value {
+            Some(_) => self.newest_age.is_some(),
+            None => !self.queue.is_empty(),
+        }
+    }
+
+    /// Record the age of the newest added element, forgetting it once it has
+    /// been pushed out of the circular buffer
+    fn set_newest_age(&mut self, age: Option<usize>) {
+        self.newest_age = age.filter(|&age| age < self.capacity);
+    }
+
+    /// Check whether the circular buffer is full
+    ///
+    /// # Returns
+    /// `true` if the `size` of the buffer has reached its `capacity`, in which
+    /// case the next addition will push out the oldest element. Buffers with
+    /// default values are always full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(2);
+    /// cbuf.add(1);
+    /// assert!(!cbuf.is_full());
+    /// cbuf.add(2);
+    /// assert!(cbuf.is_full());
+    ///
+    /// let cbuf_def = CircularBuffer::with_default(2, 0isize);
+    /// assert!(cbuf_def.is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.queue.len() >= self.capacity
+    }
+
+    /// Remove all elements from the circular buffer
+    ///
+    /// For buffers with default values, the buffer is refilled with the
+    /// default value, so that its `size` remains equal to its `capacity`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.clear();
+    /// assert_eq!(cbuf.size(), 0);
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(1);
+    /// cbuf_def.add(2);
+    /// cbuf_def.clear();
+    /// assert_eq!(cbuf_def.size(), 3);
+    /// assert_eq!(cbuf_def.remove(), Ok(0));
+    /// assert_eq!(cbuf_def.remove(), Ok(0));
+    /// assert_eq!(cbuf_def.remove(), Ok(0));
+    /// ```
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        if let Some(val) = &self.default_value {
+            self.queue.resize(self.capacity, val.clone());
+            keep_contiguous(&mut self.queue);
+        }
+        self.newest_age = None;
+    }
+
+    /// Remove all elements from the circular buffer and return them
+    ///
+    /// For buffers with default values, the returned elements include the
+    /// default-filled slots, and the buffer is then refilled with the default
+    /// value, so that its `size` remains equal to its `capacity`.
+    ///
+    /// # Returns
+    /// The removed elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(2);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// assert_eq!(cbuf.drain(), vec![2, 3]);
+    /// assert_eq!(cbuf.size(), 0);
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(1);
+    /// cbuf_def.add(2);
+    /// assert_eq!(cbuf_def.drain(), vec![0, 1, 2]);
+    /// assert_eq!(cbuf_def.size(), 3);
+    /// assert_eq!(cbuf_def.to_vec(), vec![0, 0, 0]);
+    /// ```
+    pub fn drain(&mut self) -> Vec<T> {
+        let vals = std::mem::take(&mut self.queue).into();
+        self.clear();
+        vals
+    }
+
+    /// Release memory the circular buffer no longer needs
+    ///
+    /// The elements, their order and the `capacity` limit of the circular
+    /// buffer are unchanged; only unused memory is handed back to the
+    /// allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(1000);
+    /// for i in 0..1000 {
+    ///     cbuf.add(i);
+    /// }
+    /// while cbuf.size() > 3 {
+    ///     cbuf.remove();
+    /// }
+    ///
+    /// cbuf.shrink_to_fit();
+    /// assert_eq!(cbuf.capacity(), 1000);
+    /// assert_eq!(cbuf.to_vec(), vec![997, 998, 999]);
+    ///
+    /// // Shrinking after removing from the head keeps the elements in one slice
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(16);
+    /// for i in 0..16 {
+    ///     cbuf.add(i);
+    /// }
+    /// for _ in 0..4 {
+    ///     cbuf.remove();
+    /// }
+    /// cbuf.shrink_to_fit();
+    /// assert_eq!(cbuf.as_slice().len(), cbuf.size());
+    /// assert_eq!(cbuf.as_slice(), &(4..16).collect::<Vec<_>>()[..]);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.queue.shrink_to_fit();
+        // Shrinking can wrap the elements around the end of the ring. They are
+        // rearranged in place, as `keep_contiguous` would grow the allocation
+        // straight back.
+        self.queue.make_contiguous();
+    }
+
+    /// Iterate over clones of the elements in the circular buffer
+    ///
+    /// The circular buffer itself is left untouched. For buffers with default values, any
+    /// default-filled slots are included.
+    ///
+    /// # Returns
+    /// An iterator over owned copies of the elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(1);
+    /// assert_eq!(cbuf_def.iter_cloned().collect::<Vec<_>>(), vec![0, 0, 1]);
+    /// assert_eq!(cbuf_def.size(), 3);
+    /// ```
+    pub fn iter_cloned(&self) -> impl Iterator<Item = T> + '_ {
+        self.queue.iter().cloned()
+    }
+
+    /// Get a copy of the circular buffer's contents as a vector
+    ///
+    /// Unlike `into_iter`, the circular buffer itself is left untouched. For
+    /// buffers with default values, the default-filled slots are included,
+    /// so the vector's length equals the capacity.
+    ///
+    /// # Returns
+    /// A vector of cloned elements, with the oldest at index 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(2);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// assert_eq!(cbuf.to_vec(), vec![2, 3]);
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(5);
+    /// assert_eq!(cbuf_def.to_vec(), vec![0, 0, 5]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<T> {
+        self.queue.iter().cloned().collect()
+    }
+
+    /// View the circular buffer's contents as a slice
+    ///
+    /// This avoids the allocation of `to_vec`. The slice is a live view that
+    /// borrows the circular buffer, so it cannot outlive the next mutation.
+    /// For buffers with default values, the default-filled slots are included.
+    ///
+    /// # Returns
+    /// A slice of the elements, with the oldest at index 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(2);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// assert_eq!(cbuf.as_slice(), &[2, 3]);
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        contiguous(&self.queue)
+    }
+
+    /// Clone the leading elements of the circular buffer while they match a
+    /// predicate
+    ///
+    /// The circular buffer is left unchanged. For buffers with default
+    /// values, the default-filled slots are tested like any other element.
+    ///
+    /// # Parameters
+    /// - `pred`: Predicate to test each element against, oldest first
+    ///
+    /// # Returns
+    /// Clones of the leading matching elements, oldest first, stopping at the
+    /// first element that does not match
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// cbuf.add(1);
+    /// cbuf.add(5);
+    /// cbuf.add(2);
+    /// assert_eq!(cbuf.take_while(|&x| x < 4), vec![1]);
+    /// assert_eq!(cbuf.size(), 3);
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(4, 0isize);
+    /// cbuf_def.add(7);
+    /// assert_eq!(cbuf_def.take_while(|&x| x < 4), vec![0, 0, 0]);
+    /// ```
+    pub fn take_while<F: FnMut(&T) -> bool>(&self, mut pred: F) -> Vec<T> {
+        self.queue.iter().take_while(|val| pred(val)).cloned().collect()
+    }
+
+    /// Get a reference to the element at a position in the circular buffer
+    ///
+    /// Position `0` is the head of the circular buffer, the element `peek`
+    /// returns. For buffers with default values, the default-filled slots
+    /// can be indexed too. The circular buffer is left unchanged.
+    ///
+    /// # Parameters
+    /// - `index`: Oldest-first position of the element
+    ///
+    /// # Returns
+    /// - `Some(&T)`: The element at `index`
+    /// - `None`: If `index` is out of bounds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(2);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// assert_eq!(cbuf.get(0), Some(&cbuf.peek().unwrap()));
+    /// assert_eq!(cbuf.get(1), Some(&3));
+    /// assert_eq!(cbuf.get(2), None);
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(5);
+    /// assert_eq!(cbuf_def.get(0), Some(&0));
+    /// assert_eq!(cbuf_def.get(2), Some(&5));
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.queue.get(index)
+    }
+
+    /// Iterate over the elements of the circular buffer
+    ///
+    /// The circular buffer is borrowed, not consumed or modified.
+    ///
+    /// # Returns
+    /// An iterator over references to the elements, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(2);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// assert_eq!(cbuf.iter().collect::<Vec<_>>(), vec![&2, &3]);
+    /// assert_eq!(cbuf.size(), 2);
+    /// assert_eq!(cbuf.peek(), Ok(2));
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        contiguous(&self.queue).iter()
+    }
+
+    /// Peek at the tail of the circular buffer
+    ///
+    /// The tail is the most recently added element, the last one scheduled
+    /// for removal.
+    ///
+    /// For buffers with default values, this is the default value until an
+    /// element is added.
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The newest element in the circular buffer
+    /// - `Err(QueueError::Empty)`
+    ///
+    /// # Errors
+    /// Returns an error if an attempt is made to peek into an empty circular buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// assert_eq!(cbuf_def.peek_back(), Ok(0));
+    ///
+    /// cbuf_def.add(1);
+    /// cbuf_def.add(2);
+    /// assert_eq!(cbuf_def.peek(), Ok(0));
+    /// assert_eq!(cbuf_def.peek_back(), Ok(2));
+    ///
+    /// let cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// assert_eq!(cbuf.peek_back(), Err(QueueError::Empty));
+    /// ```
+    pub fn peek_back(&self) -> Result<T, QueueError> {
+        self.queue.back().cloned().ok_or(QueueError::Empty)
+    }
+}
+
+impl<T: Clone + PartialOrd> CircularBuffer<T> {
+    /// Check whether the circular buffer is monotonically increasing
+    ///
+    /// Elements are compared from oldest to newest. An empty or
+    /// single-element buffer is trivially monotonic. For buffers with default
+    /// values, the default-filled slots are part of the sequence.
+    ///
+    /// # Returns
+    /// `true` if every element is greater than or equal to the one before it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// assert!(cbuf.is_monotonic_increasing());
+    /// assert!(!cbuf.is_monotonic_decreasing());
+    ///
+    /// // Overflow pushes out the 1, leaving [2, 3, 0]
+    /// cbuf.add(0);
+    /// assert!(!cbuf.is_monotonic_increasing());
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(5);
+    /// assert!(cbuf_def.is_monotonic_increasing());
+    /// ```
+    pub fn is_monotonic_increasing(&self) -> bool {
+        self.queue.iter().zip(self.queue.iter().skip(1)).all(|(a, b)| a <= b)
+    }
+
+    /// Check whether the circular buffer is monotonically decreasing
+    ///
+    /// Elements are compared from oldest to newest. An empty or
+    /// single-element buffer is trivially monotonic. For buffers with default
+    /// values, the default-filled slots are part of the sequence.
+    ///
+    /// # Returns
+    /// `true` if every element is less than or equal to the one before it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// assert!(cbuf.is_monotonic_decreasing());
+    /// cbuf.add(3);
+    /// cbuf.add(2);
+    /// cbuf.add(1);
+    /// assert!(cbuf.is_monotonic_decreasing());
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(5);
+    /// assert!(!cbuf_def.is_monotonic_decreasing());
+    /// ```
+    pub fn is_monotonic_decreasing(&self) -> bool {
+        self.queue.iter().zip(self.queue.iter().skip(1)).all(|(a, b)| a >= b)
+    }
+
+    /// Get the rank of a value among the elements of the circular buffer
+    ///
+    /// The rank is the number of elements strictly less than the value, which
+    /// is the position it would take if inserted into a sorted copy of the
+    /// circular buffer. For buffers with default values, the default-filled
+    /// slots are counted.
+    ///
+    /// # Parameters
+    /// - `val`: Value to rank
+    ///
+    /// # Returns
+    /// The number of elements less than `val`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(5);
+    /// assert_eq!(cbuf_def.rank(&1), 2);
+    /// assert_eq!(cbuf_def.rank(&0), 0);
+    /// ```
+    pub fn rank(&self, val: &T) -> usize {
+        self.queue.iter().filter(|&el| el < val).count()
+    }
+
+    /// Find the position of the largest element in the circular buffer
+    ///
+    /// Positions are counted from the oldest element. If several elements
+    /// are equally large, the oldest one is chosen.
+    ///
+    /// # Returns
+    /// - `Some(usize)`: The position of the largest element
+    /// - `None`: If the buffer is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(5);
+    /// assert_eq!(cbuf.argmax(), None);
+    ///
+    /// for &val in &[3, 9, 4, 9, 1] {
+    ///     cbuf.add(val);
+    /// }
+    /// assert_eq!(cbuf.argmax(), Some(1));
+    /// ```
+    pub fn argmax(&self) -> Option<usize> {
+        self.position_by(|candidate, best| candidate > best)
+    }
+
+    /// Find the position of the smallest element in the circular buffer
+    ///
+    /// Positions are counted from the oldest element. If several elements
+    /// are equally small, the oldest one is chosen.
+    ///
+    /// # Returns
+    /// - `Some(usize)`: The position of the smallest element
+    /// - `None`: If the buffer is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(5);
+    /// assert_eq!(cbuf.argmin(), None);
+    ///
+    /// for &val in &[3, 1, 4, 1, 5] {
+    ///     cbuf.add(val);
+    /// }
+    /// assert_eq!(cbuf.argmin(), Some(1));
+    /// ```
+    pub fn argmin(&self) -> Option<usize> {
+        self.position_by(|candidate, best| candidate < best)
+    }
+
+    // Finds the position of the element that `better` prefers over all those
+    // before it, keeping the earliest one on ties
+    fn position_by<F: Fn(&T, &T) -> bool>(&self, better: F) -> Option<usize> {
+        let mut positions = 0..self.queue.len();
+        let first = positions.next()?;
+        Some(positions.fold(first, |best, i| {
+            if better(&self.queue[i], &self.queue[best]) {
+                i
+            } else {
+                best
+            }
+        }))
+    }
+}
+
+impl<T: Clone + Eq + Hash> CircularBuffer<T> {
+    /// Count the occurrences of each distinct element in the circular buffer
+    ///
+    /// For buffers with default values, the default-filled slots are counted.
+    ///
+    /// # Returns
+    /// A map from each distinct element to the number of times it occurs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf_def = CircularBuffer::with_default(4, 0isize);
+    /// cbuf_def.add(5);
+    /// let counts = cbuf_def.tally();
+    /// assert_eq!(counts[&0], 3);
+    /// assert_eq!(counts[&5], 1);
+    /// ```
+    pub fn tally(&self) -> HashMap<T, usize> {
+        let mut counts = HashMap::new();
+        for val in &self.queue {
+            *counts.entry(val.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Compute the Shannon entropy of the circular buffer's contents
+    ///
+    /// The entropy, in bits, of the empirical distribution of elements
+    /// measures how varied the window is. For buffers with default values,
+    /// the default-filled slots are counted.
+    ///
+    /// # Returns
+    /// The entropy in bits, or `0.0` if the buffer is empty or holds a
+    /// single distinct value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// // A uniform window over four values has the maximum entropy of 2 bits
+    /// let mut cbuf: CircularBuffer<char> = CircularBuffer::new(4);
+    /// for c in "abcd".chars() {
+    ///     cbuf.add(c);
+    /// }
+    /// assert_eq!(cbuf.entropy(), 2.0);
+    ///
+    /// // A constant window has no entropy
+    /// let cbuf_def = CircularBuffer::with_default(4, 'a');
+    /// assert_eq!(cbuf_def.entropy(), 0.0);
+    ///
+    /// let empty: CircularBuffer<char> = CircularBuffer::new(4);
+    /// assert_eq!(empty.entropy(), 0.0);
+    /// ```
+    pub fn entropy(&self) -> f64 {
+        let total = self.queue.len() as f64;
+        self.tally()
+            .values()
+            .map(|&count| {
+                let p = count as f64 / total;
+                -p * p.log2()
+            })
+            .sum::<f64>()
+            .max(0.0)
+    }
+}
+
+impl<T: Clone + PartialEq> CircularBuffer<T> {
+    /// Get the value that at least `threshold` elements of the window agree on
+    ///
+    /// This debounces a noisy signal: a value is only reported once enough of
+    /// the window holds it. If several values meet the threshold, the most
+    /// frequent one is returned, with ties going to the oldest. For buffers
+    /// with default values, the default-filled slots are counted.
+    ///
+    /// # Returns
+    /// `Some(value)` for the most frequent element if it occurs at least
+    /// `threshold` times, `None` otherwise
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<bool> = CircularBuffer::new(5);
+    /// cbuf.add(true);
+    /// cbuf.add(false);
+    /// cbuf.add(true);
+    /// assert_eq!(cbuf.stable_value(3), None);
+    ///
+    /// cbuf.add(true);
+    /// assert_eq!(cbuf.stable_value(3), Some(true));
+    ///
+    /// // Defaults count towards the tally
+    /// let mut cbuf_def = CircularBuffer::with_default(4, 0isize);
+    /// cbuf_def.add(1);
+    /// assert_eq!(cbuf_def.stable_value(3), Some(0));
+    /// assert_eq!(cbuf_def.stable_value(4), None);
+    /// ```
+    pub fn stable_value(&self, threshold: usize) -> Option<T> {
+        let mut best: Option<(&T, usize)> = None;
+        for val in &self.queue {
+            let count = self.queue.iter().filter(|other| *other == val).count();
+            if best.is_none_or(|(_, max)| count > max) {
+                best = Some((val, count));
+            }
+        }
+        best.filter(|&(_, count)| count >= threshold)
+            .map(|(val, _)| val.clone())
+    }
+
+    /// Check whether the circular buffer contains a value
+    ///
+    /// For buffers with default values, the default-filled slots are
+    /// searched too, so the default value is always contained.
+    ///
+    /// # Parameters
+    /// - `val`: Value to search for
+    ///
+    /// # Returns
+    /// `true` if any element of the circular buffer is equal to `val`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// assert!(cbuf.contains(&2));
+    /// assert!(!cbuf.contains(&4));
+    ///
+    /// let cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// assert!(cbuf_def.contains(&0));
+    /// ```
+    pub fn contains(&self, val: &T) -> bool {
+        self.queue.contains(val)
+    }
+}
+
+impl<T: Clone + Sum> CircularBuffer<T> {
+    /// Sum the newest `n` elements of the circular buffer
+    ///
+    /// If the buffer holds fewer than `n` elements, all of them are summed.
+    /// For buffers with default values, the default-filled slots are
+    /// included.
+    ///
+    /// # Parameters
+    /// - `n`: Number of elements to sum
+    ///
+    /// # Returns
+    /// The sum of the newest `min(n, size)` elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(4);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// cbuf.add(4);
+    ///
+    /// assert_eq!(cbuf.sum_of_last(2), 7);
+    /// assert_eq!(cbuf.sum_of_last(100), 10);
+    /// assert_eq!(cbuf.sum_of_last(0), 0);
+    /// ```
+    pub fn sum_of_last(&self, n: usize) -> T {
+        self.queue
+            .iter()
+            .skip(self.queue.len().saturating_sub(n))
+            .cloned()
+            .sum()
+    }
+
+    /// Sum the elements of the circular buffer within a range of positions
+    ///
+    /// Positions are counted from the oldest element. An empty range sums to
+    /// the zero value of `T`.
+    ///
+    /// # Parameters
+    /// - `start`: Position of the first element to sum
+    /// - `end`: Position after the last element to sum
+    ///
+    /// # Returns
+    /// - `Ok(T)`: The sum of the elements in `[start, end)`
+    /// - `Err(QueueError::OutOfBounds)`
+    ///
+    /// # Errors
+    /// Returns an error if `start` is greater than `end`, or if `end` is
+    /// greater than the buffer's `size`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(4);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// cbuf.add(4);
+    ///
+    /// assert_eq!(cbuf.range_sum(1, 3), Ok(5));
+    /// assert_eq!(cbuf.range_sum(0, 4), Ok(10));
+    /// assert_eq!(cbuf.range_sum(2, 2), Ok(0));
+    /// assert_eq!(cbuf.range_sum(3, 5), Err(QueueError::OutOfBounds));
+    /// assert_eq!(cbuf.range_sum(3, 2), Err(QueueError::OutOfBounds));
+    /// ```
+    pub fn range_sum(&self, start: usize, end: usize) -> Result<T, QueueError> {
+        if start > end || end > self.queue.len() {
+            return Err(QueueError::OutOfBounds);
+        }
+        Ok(self.queue.range(start..end).cloned().sum())
+    }
+}
+
+impl<T: Clone + PartialOrd + Sub<Output = T>> CircularBuffer<T> {
+    /// Compute the peak-to-peak range of the circular buffer
+    ///
+    /// This is the difference between the largest and smallest elements, a
+    /// quick measure of a signal's amplitude. For buffers with default
+    /// values, the default-filled slots are considered.
+    ///
+    /// # Returns
+    /// `Some(max - min)`, or `None` if the buffer is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(4);
+    /// assert_eq!(cbuf.peak_to_peak(), None);
+    ///
+    /// cbuf.add(3);
+    /// cbuf.add(-2);
+    /// cbuf.add(7);
+    /// cbuf.add(1);
+    /// assert_eq!(cbuf.peak_to_peak(), Some(9));
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0.0);
+    /// cbuf_def.add(2.5);
+    /// assert_eq!(cbuf_def.peak_to_peak(), Some(2.5));
+    /// ```
+    pub fn peak_to_peak(&self) -> Option<T> {
+        let max = self.queue[self.argmax()?].clone();
+        let min = self.queue[self.argmin()?].clone();
+        Some(max - min)
+    }
+}
+
+impl CircularBuffer<f64> {
+    /// Compute the exponentially-weighted moving average of the buffer
+    ///
+    /// The average is computed over the elements from oldest to newest using
+    /// the recursion `s_i = alpha * x_i + (1 - alpha) * s_{i-1}`, starting
+    /// from `s_0 = x_0`. For buffers with default values, the default-filled
+    /// slots are included.
+    ///
+    /// # Parameters
+    /// - `alpha`: Smoothing factor, in the range `(0, 1]`
+    ///
+    /// # Returns
+    /// - `Some(f64)`: The smoothed value after the newest element
+    /// - `None`: If the buffer is empty
+    ///
+    /// # Panics
+    /// Panics if `alpha` is not in the range `(0, 1]`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(3);
+    /// assert_eq!(cbuf.ewma(0.5), None);
+    ///
+    /// cbuf.add(1.0);
+    /// cbuf.add(2.0);
+    /// cbuf.add(3.0);
+    ///
+    /// // 1.0 -> 0.5 * 2.0 + 0.5 * 1.0 = 1.5 -> 0.5 * 3.0 + 0.5 * 1.5 = 2.25
+    /// assert_eq!(cbuf.ewma(0.5), Some(2.25));
+    ///
+    /// // An alpha of 1 tracks the newest element
+    /// assert_eq!(cbuf.ewma(1.0), Some(3.0));
+    /// ```
+    pub fn ewma(&self, alpha: f64) -> Option<f64> {
+        assert!(alpha > 0.0 && alpha <= 1.0, "alpha must be in the range (0, 1]");
+
+        let mut vals = self.queue.iter();
+        let first = *vals.next()?;
+        Some(vals.fold(first, |acc, &val| alpha * val + (1.0 - alpha) * acc))
+    }
+
+    /// Check whether the newest element is a spike relative to the rest
+    ///
+    /// The newest element is compared against the mean of all _other_
+    /// elements in the buffer; it is excluded from its own baseline so that
+    /// a large spike does not drag the mean towards itself.
+    ///
+    /// # Parameters
+    /// - `threshold`: Maximum allowed deviation from the baseline mean
+    ///
+    /// # Returns
+    /// `true` if `|newest - mean_of_rest| > threshold`. Always `false` if the
+    /// buffer holds fewer than two elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(4);
+    /// cbuf.add(1.0);
+    /// assert!(!cbuf.is_spike(0.5));
+    ///
+    /// cbuf.add(1.0);
+    /// cbuf.add(1.0);
+    /// assert!(!cbuf.is_spike(0.5));
+    ///
+    /// cbuf.add(10.0);
+    /// assert!(cbuf.is_spike(0.5));
+    /// ```
+    pub fn is_spike(&self, threshold: f64) -> bool {
+        match self.queue.back() {
+            Some(newest) if self.queue.len() > 1 => {
+                let rest = self.queue.len() - 1;
+                let mean = self.queue.iter().take(rest).sum::<f64>() / rest as f64;
+                (newest - mean).abs() > threshold
+            }
+            _ => false,
+        }
+    }
+
+    /// Compute the normalized autocorrelation of the buffer at a given lag
+    ///
+    /// Uses the standard estimator with the mean of the buffer `m`
+    /// subtracted from each element:
+    /// `r(lag) = sum((x_i - m) * (x_{i+lag} - m)) / sum((x_i - m)^2)`. A value
+    /// close to 1 indicates that the signal repeats with a period of `lag`.
+    ///
+    /// # Parameters
+    /// - `lag`: Offset, in elements, to correlate the buffer against
+    ///
+    /// # Returns
+    /// - `Some(f64)`: The autocorrelation at `lag`
+    /// - `None`: If `lag` is not less than the buffer's `size`, or if all
+    ///   elements are equal (the signal has zero variance)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(20);
+    /// for i in 0..20 {
+    ///     cbuf.add(if i % 2 == 0 { 1.0 } else { -1.0 });
+    /// }
+    ///
+    /// assert_eq!(cbuf.autocorrelation(0), Some(1.0));
+    /// assert!(cbuf.autocorrelation(2).unwrap() > 0.85);
+    /// assert!(cbuf.autocorrelation(1).unwrap() < -0.85);
+    /// assert_eq!(cbuf.autocorrelation(20), None);
+    /// ```
+    pub fn autocorrelation(&self, lag: usize) -> Option<f64> {
+        let size = self.queue.len();
+        if lag >= size {
+            return None;
+        }
+
+        let mean = self.queue.iter().sum::<f64>() / size as f64;
+        let variance: f64 = self.queue.iter().map(|val| (val - mean).powi(2)).sum();
+        if variance == 0.0 {
+            return None;
+        }
+
+        let covariance: f64 = self
+            .queue
+            .iter()
+            .zip(self.queue.iter().skip(lag))
+            .map(|(a, b)| (a - mean) * (b - mean))
+            .sum();
+        Some(covariance / variance)
+    }
+
+    /// Read a linearly-interpolated value at a fractional position
+    ///
+    /// Positions are counted from the oldest element, so a position of `1.5`
+    /// lies halfway between the elements at positions 1 and 2. Integer
+    /// positions read the element directly.
+    ///
+    /// # Parameters
+    /// - `index`: Fractional oldest-first position to read
+    ///
+    /// # Returns
+    /// - `Some(f64)`: The interpolated value
+    /// - `None`: If `index` is outside of `[0, size - 1]`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(3);
+    /// cbuf.add(1.0);
+    /// cbuf.add(3.0);
+    /// cbuf.add(4.0);
+    ///
+    /// // Integer indices
+    /// assert_eq!(cbuf.lerp_at(0.0), Some(1.0));
+    /// assert_eq!(cbuf.lerp_at(2.0), Some(4.0));
+    ///
+    /// // Midpoints
+    /// assert_eq!(cbuf.lerp_at(0.5), Some(2.0));
+    /// assert_eq!(cbuf.lerp_at(1.25), Some(3.25));
+    ///
+    /// // Out of range
+    /// assert_eq!(cbuf.lerp_at(-0.5), None);
+    /// assert_eq!(cbuf.lerp_at(2.5), None);
+    /// ```
+    pub fn lerp_at(&self, index: f64) -> Option<f64> {
+        let last = self.queue.len().checked_sub(1)?;
+        if !(0.0..=last as f64).contains(&index) {
+            return None;
+        }
+
+        let lower = index.floor() as usize;
+        let fraction = index - lower as f64;
+        if fraction == 0.0 {
+            return Some(self.queue[lower]);
+        }
+
+        let (a, b) = (self.queue[lower], self.queue[lower + 1]);
+        Some(a + (b - a) * fraction)
+    }
+
+    /// Resample the buffer to a new sample interval
+    ///
+    /// The elements are treated as samples taken every `old_dt`, oldest
+    /// first, and linearly interpolated (see `lerp_at`) at every `new_dt`
+    /// over the same span of time, starting with the oldest element. The
+    /// span is not extended, so when it is not a whole multiple of `new_dt`
+    /// the newest element is not sampled.
+    ///
+    /// # Parameters
+    /// - `old_dt`: Interval between the buffered samples
+    /// - `new_dt`: Interval between the resampled values
+    ///
+    /// # Returns
+    /// The resampled values, oldest first. Empty if the buffer is empty, and
+    /// a copy of the elements if the intervals are equal.
+    ///
+    /// # Panics
+    /// Panics if either interval is not positive
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(4);
+    /// for val in vec![0.0, 2.0, 4.0, 3.0] {
+    ///     cbuf.add(val);
+    /// }
+    ///
+    /// // Upsampling
+    /// assert_eq!(cbuf.resample_to_rate(1.0, 0.5), vec![0.0, 1.0, 2.0, 3.0, 4.0, 3.5, 3.0]);
+    ///
+    /// // Downsampling
+    /// assert_eq!(cbuf.resample_to_rate(1.0, 1.5), vec![0.0, 3.0, 3.0]);
+    ///
+    /// // Equal intervals
+    /// assert_eq!(cbuf.resample_to_rate(0.1, 0.1), vec![0.0, 2.0, 4.0, 3.0]);
+    ///
+    /// let empty: CircularBuffer<f64> = CircularBuffer::new(4);
+    /// assert!(empty.resample_to_rate(1.0, 0.5).is_empty());
+    /// ```
+    pub fn resample_to_rate(&self, old_dt: f64, new_dt: f64) -> Vec<f64> {
+        assert!(old_dt > 0.0 && new_dt > 0.0, "sample intervals must be positive");
+
+        let last = match self.queue.len().checked_sub(1) {
+            Some(last) => last as f64,
+            None => return vec![],
+        };
+        if old_dt == new_dt {
+            return self.queue.iter().copied().collect();
         }
+
+        // Allow for rounding error when the span is a whole multiple of `new_dt`
+        let steps = (last * old_dt / new_dt + 1e-9).floor() as usize;
+        (0..=steps)
+            .filter_map(|step| self.lerp_at((step as f64 * new_dt / old_dt).min(last)))
+            .collect()
     }
 
-    /// Gets the size of the buffer
+    /// Count the sign changes between consecutive elements of the buffer
+    ///
+    /// Elements are scanned from oldest to newest. An element that is exactly
+    /// zero is treated as continuing the sign of the element before it, so a
+    /// signal that touches zero and returns to the same sign does not cross,
+    /// while one that passes through zero to the opposite sign crosses once.
     ///
     /// # Returns
-    /// The number of elements in the buffer.
+    /// The number of zero crossings
     ///
     /// # Examples
     ///
     /// ```
     /// # use queues::*;
-    /// let mut buf: Buffer<isize> = Buffer::new(3);
-    /// assert_eq!(buf.size(), 0);
-    /// buf.add(42);
-    /// assert_eq!(buf.size(), 1);
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(9);
+    /// for &val in &[1.0, -1.0, 0.0, -2.0, 3.0, 0.0, 0.0, 4.0, -5.0] {
+    ///     cbuf.add(val);
+    /// }
+    /// assert_eq!(cbuf.zero_crossings(), 3);
+    ///
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(3);
+    /// cbuf.add(1.0);
+    /// cbuf.add(0.0);
+    /// cbuf.add(-1.0);
+    /// assert_eq!(cbuf.zero_crossings(), 1);
     /// ```
-    fn size(&self) -> usize {
-        self.queue.len()
+    pub fn zero_crossings(&self) -> usize {
+        let mut crossings = 0;
+        let mut sign = None;
+        for &val in self.queue.iter().filter(|&&val| val != 0.0) {
+            let positive = val > 0.0;
+            if sign.is_some_and(|prev| prev != positive) {
+                crossings += 1;
+            }
+            sign = Some(positive);
+        }
+        crossings
     }
-}
 
-/// Represents a FIFO `CircularBuffer<T>` data structure.
-///
-/// This structure is a limited capacity queue, with optional provisions
-/// for default values. Under normal circumstances, the `size` of the
-/// queue grows until it reaches its `capacity`, at which point any
-/// further additions push out its oldest member.
-///
-/// If default values are specified, then the `size` of the queue
-/// is always equal to its `capacity`, with empty slots occupied by the
-/// specified default value.
-///
-/// # Type parameters
-/// - `T`: Any type that implements the `Clone` trait.
-///
-/// # Examples
-///
-/// ```
-/// # use queues::*;
-/// # fn main() {
-/// let mut cbuf = CircularBuffer::<isize>::new(3);
-/// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
-///
-/// // Check sizes
-/// assert_eq!(cbuf.size(), 0);
-/// assert_eq!(cbuf_def.size(), 3);
-///
-/// // Add elements
-/// cbuf.add(6);
-/// cbuf_def.add(7);
-///
-/// // Peek at the next element scheduled for removal
-/// assert_eq!(cbuf.peek().unwrap(), 6);
-/// assert_eq!(cbuf_def.peek().unwrap(), 0);
-/// # }
-/// ```
-#[derive(Debug)]
-pub struct CircularBuffer<T: Clone> {
-    queue: Vec<T>,
-    capacity: usize,
-    default_value: Option<T>,
-}
+    /// Compute the median of the elements in the buffer
+    ///
+    /// For an even number of elements, the median is the mean of the two
+    /// middle values. For buffers with default values, the default-filled
+    /// slots are included.
+    ///
+    /// # Returns
+    /// - `Some(f64)`: The median element
+    /// - `None`: If the buffer is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(4);
+    /// assert_eq!(cbuf.median(), None);
+    ///
+    /// cbuf.add(5.0);
+    /// cbuf.add(1.0);
+    /// cbuf.add(100.0);
+    /// assert_eq!(cbuf.median(), Some(5.0));
+    ///
+    /// cbuf.add(2.0);
+    /// assert_eq!(cbuf.median(), Some(3.5));
+    /// ```
+    pub fn median(&self) -> Option<f64> {
+        let mut sorted: Vec<f64> = self.queue.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
 
-impl<T: Clone> CircularBuffer<T> {
-    /// Default `CircularBuffer<T>` initializer
+        let mid = sorted.len() / 2;
+        match sorted.len() {
+            0 => None,
+            len if len % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+            _ => Some(sorted[mid]),
+        }
+    }
+
+    /// Downsample the buffer by averaging consecutive blocks of elements
+    ///
+    /// The elements are split, oldest first, into blocks of `factor`
+    /// elements, and each block is replaced by its mean. A trailing partial
+    /// block is averaged over the elements it holds.
+    ///
+    /// # Parameters
+    /// - `factor`: Number of elements per block
     ///
     /// # Returns
-    /// A new, empty `CircularBuffer<T>`
+    /// The mean of each block, oldest first
+    ///
+    /// # Panics
+    /// Panics if `factor` is zero
     ///
     /// # Examples
     ///
     /// ```
     /// # use queues::*;
-    /// let cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
-    /// assert_eq!(cbuf.size(), 0);
-    /// assert_eq!(cbuf.capacity(), 3);
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(8);
+    /// for i in 1..=8 {
+    ///     cbuf.add(i as f64);
+    /// }
+    ///
+    /// assert_eq!(cbuf.downsample_mean(2), vec![1.5, 3.5, 5.5, 7.5]);
+    /// assert_eq!(cbuf.downsample_mean(3), vec![2.0, 5.0, 7.5]);
     /// ```
-    pub fn new(capacity: usize) -> CircularBuffer<T> {
-        CircularBuffer {
-            queue: vec![],
-            capacity,
-            default_value: None,
+    pub fn downsample_mean(&self, factor: usize) -> Vec<f64> {
+        assert!(factor != 0, "downsampling factor must be non-zero");
+
+        let vals: Vec<f64> = self.queue.iter().copied().collect();
+        vals.chunks(factor)
+            .map(|block| block.iter().sum::<f64>() / block.len() as f64)
+            .collect()
+    }
+
+    /// Compute the slope of the least-squares line fitted through the buffer
+    ///
+    /// Each element's oldest-first position (0, 1, 2, ...) is used as its x
+    /// value, and the element itself as its y value. A positive slope
+    /// indicates an upward trend.
+    ///
+    /// # Returns
+    /// - `Some(f64)`: The slope of the best-fit line
+    /// - `None`: If the buffer holds fewer than two elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(4);
+    /// cbuf.add(3.0);
+    /// assert_eq!(cbuf.trend_slope(), None);
+    ///
+    /// cbuf.add(4.0);
+    /// cbuf.add(5.0);
+    /// cbuf.add(6.0);
+    /// assert_eq!(cbuf.trend_slope(), Some(1.0));
+    ///
+    /// let flat = CircularBuffer::with_default(4, 2.0);
+    /// assert_eq!(flat.trend_slope(), Some(0.0));
+    /// ```
+    pub fn trend_slope(&self) -> Option<f64> {
+        let size = self.queue.len();
+        if size < 2 {
+            return None;
         }
+
+        let x_mean = (size - 1) as f64 / 2.0;
+        let y_mean = self.queue.iter().sum::<f64>() / size as f64;
+        let (covariance, variance) = self.queue.iter().enumerate().fold((0.0, 0.0), |(cov, var), (x, y)| {
+            let dx = x as f64 - x_mean;
+            (cov + dx * (y - y_mean), var + dx * dx)
+        });
+        Some(covariance / variance)
     }
 
-    /// Create a `CircularBuffer<T>` with default values
+    /// Compute the weighted mean of the elements in the buffer
+    ///
+    /// Each weight applies to the element at the same oldest-first position,
+    /// so `weights` must have exactly one entry per element. For buffers with
+    /// default values, the default-filled slots are included.
+    ///
+    /// # Parameters
+    /// - `weights`: Weight of each element, oldest first
     ///
     /// # Returns
-    /// A new `CircularBuffer<T>` filled with default values
+    /// - `Some(f64)`: The weighted mean
+    /// - `None`: If the buffer is empty, if the length of `weights` differs
+    ///   from the buffer's `size`, or if the weights sum to zero
     ///
     /// # Examples
     ///
     /// ```
     /// # use queues::*;
-    /// let cbuf_def = CircularBuffer::with_default(3, -1isize);
-    /// assert_eq!(cbuf_def.size(), 3);
-    /// assert_eq!(cbuf_def.capacity(), 3);
-    /// assert_eq!(cbuf_def.peek(), Ok(-1));
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(3);
+    /// cbuf.add(1.0);
+    /// cbuf.add(2.0);
+    /// cbuf.add(4.0);
+    ///
+    /// // (1 * 1 + 2 * 1 + 4 * 2) / 4
+    /// assert_eq!(cbuf.weighted_mean(&[1.0, 1.0, 2.0]), Some(2.75));
+    /// assert_eq!(cbuf.weighted_mean(&[0.0, 0.0, 1.0]), Some(4.0));
+    ///
+    /// assert_eq!(cbuf.weighted_mean(&[1.0, 1.0]), None);
+    /// assert_eq!(cbuf.weighted_mean(&[0.0, 0.0, 0.0]), None);
     /// ```
-    pub fn with_default(capacity: usize, default_value: T) -> CircularBuffer<T> {
-        let queue = vec![default_value.clone(); capacity];
+    pub fn weighted_mean(&self, weights: &[f64]) -> Option<f64> {
+        if self.queue.is_empty() || weights.len() != self.queue.len() {
+            return None;
+        }
 
-        CircularBuffer {
-            queue,
-            capacity,
-            default_value: Some(default_value),
+        let total_weight: f64 = weights.iter().sum();
+        if total_weight == 0.0 {
+            return None;
         }
+
+        let weighted_sum: f64 = self.queue.iter().zip(weights).map(|(val, weight)| val * weight).sum();
+        Some(weighted_sum / total_weight)
     }
 
-    /// Gets the capacity of the `CircularBuffer<T>`
+    /// Compute the running sum of the elements in the buffer
+    ///
+    /// For buffers with default values, the default-filled slots are
+    /// included.
     ///
     /// # Returns
-    /// The number of allowed elements in the buffer
+    /// The sum of all elements up to and including each position, oldest
+    /// first
     ///
     /// # Examples
     ///
     /// ```
-    /// # use queues::CircularBuffer;
-    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
-    /// assert_eq!(cbuf.capacity(), 3);
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(4);
+    /// cbuf.add(1.0);
+    /// cbuf.add(2.0);
+    /// cbuf.add(3.0);
+    /// cbuf.add(-1.0);
+    /// assert_eq!(cbuf.cumulative_sum(), vec![1.0, 3.0, 6.0, 5.0]);
     /// ```
-    pub fn capacity(&self) -> usize {
-        self.capacity
+    pub fn cumulative_sum(&self) -> Vec<f64> {
+        self.queue
+            .iter()
+            .scan(0.0, |total, val| {
+                *total += val;
+                Some(*total)
+            })
+            .collect()
+    }
+
+    /// Integrate the elements of the buffer using the trapezoidal rule
+    ///
+    /// The elements are treated as samples of a signal taken `dt` apart. For
+    /// buffers with default values, the default-filled slots are included.
+    ///
+    /// # Parameters
+    /// - `dt`: Spacing between consecutive samples
+    ///
+    /// # Returns
+    /// The total area under the signal. Buffers holding fewer than two
+    /// elements have no area, and return `0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(3);
+    /// cbuf.add(0.0);
+    /// cbuf.add(2.0);
+    /// cbuf.add(2.0);
+    ///
+    /// // 0.5 * ((0 + 2) / 2 + (2 + 2) / 2)
+    /// assert_eq!(cbuf.integrate_trapezoidal(0.5), 1.5);
+    /// ```
+    pub fn integrate_trapezoidal(&self, dt: f64) -> f64 {
+        let pairs = self.queue.iter().zip(self.queue.iter().skip(1));
+        pairs.map(|(a, b)| (a + b) / 2.0).sum::<f64>() * dt
+    }
+
+    /// Compute the root-mean-square of the elements in the buffer
+    ///
+    /// For buffers with default values, the default-filled slots are
+    /// included.
+    ///
+    /// # Returns
+    /// - `Some(f64)`: The square root of the mean of the squared elements
+    /// - `None`: If the buffer is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(4);
+    /// assert_eq!(cbuf.rms(), None);
+    ///
+    /// cbuf.add(1.0);
+    /// cbuf.add(-1.0);
+    /// cbuf.add(3.0);
+    /// cbuf.add(-3.0);
+    ///
+    /// // sqrt((1 + 1 + 9 + 9) / 4)
+    /// assert_eq!(cbuf.rms(), Some(5f64.sqrt()));
+    /// ```
+    pub fn rms(&self) -> Option<f64> {
+        if self.queue.is_empty() {
+            return None;
+        }
+        let mean_square = self.queue.iter().map(|val| val * val).sum::<f64>() / self.queue.len() as f64;
+        Some(mean_square.sqrt())
+    }
+
+    /// Cross-correlate the buffer with a kernel
+    ///
+    /// Computes the valid cross-correlation, sliding `kernel` over the buffer
+    /// from oldest to newest without padding: each output is the dot product
+    /// of `kernel` with the elements it overlaps. Peaks in the output mark
+    /// where the buffered signal best matches the kernel. For buffers with
+    /// default values, the default-filled slots are included.
+    ///
+    /// # Parameters
+    /// - `kernel`: Template to correlate the buffer with
+    ///
+    /// # Returns
+    /// `size - kernel.len() + 1` correlation values, or an empty vector if
+    /// the kernel is longer than the buffer
+    ///
+    /// # Panics
+    /// Panics if `kernel` is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(4);
+    /// for val in vec![1.0, 2.0, 3.0, 4.0] {
+    ///     cbuf.add(val);
+    /// }
+    ///
+    /// // [1*1 + 2*0 + 3*-1, 2*1 + 3*0 + 4*-1]
+    /// assert_eq!(cbuf.correlate(&[1.0, 0.0, -1.0]), vec![-2.0, -2.0]);
+    ///
+    /// // The kernel is longer than the buffer
+    /// assert!(cbuf.correlate(&[1.0; 5]).is_empty());
+    /// ```
+    pub fn correlate(&self, kernel: &[f64]) -> Vec<f64> {
+        assert!(!kernel.is_empty(), "kernel must not be empty");
+
+        let vals: Vec<f64> = self.queue.iter().copied().collect();
+        vals.windows(kernel.len())
+            .map(|window| window.iter().zip(kernel).map(|(val, weight)| val * weight).sum())
+            .collect()
+    }
+
+    /// Find where the buffered signal jumps by more than `threshold`
+    ///
+    /// Each change point is the oldest-first index of an element whose
+    /// absolute difference from the element before it exceeds `threshold`,
+    /// so the indices split the buffer into stable runs. For buffers with
+    /// default values, the default-filled slots are included.
+    ///
+    /// # Parameters
+    /// - `threshold`: Largest difference between consecutive elements that
+    ///   is not a change
+    ///
+    /// # Returns
+    /// The index of the first element of each new run, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<f64> = CircularBuffer::new(7);
+    /// for val in vec![1.0, 1.1, 0.9, 5.0, 5.2, 1.0, 1.1] {
+    ///     cbuf.add(val);
+    /// }
+    /// assert_eq!(cbuf.change_points(1.0), vec![3, 5]);
+    ///
+    /// // A series without jumps
+    /// let mut cbuf_def = CircularBuffer::with_default(4, 0.0);
+    /// cbuf_def.add(0.5);
+    /// assert!(cbuf_def.change_points(1.0).is_empty());
+    /// ```
+    pub fn change_points(&self, threshold: f64) -> Vec<usize> {
+        self.queue
+            .iter()
+            .zip(self.queue.iter().skip(1))
+            .enumerate()
+            .filter(|(_, (prev, next))| (*next - *prev).abs() > threshold)
+            .map(|(i, _)| i + 1)
+            .collect()
     }
 }
 
 impl<T: Clone> IsQueue<T> for CircularBuffer<T> {
     /// Adds an element to a circular buffer
     ///
+    /// Evicting the oldest element on overflow takes amortized constant time,
+    /// regardless of the capacity of the buffer: the circular buffer keeps its
+    /// elements in a single slice, which occasionally needs an O(n)
+    /// rearrangement when the storage wraps.
+    ///
     /// # Parameters
     /// - `val`: Value to add to the buffer
     ///
     /// # Returns
     /// - `Ok(Some(T))`: The oldest value in the buffer, in case the addition
-    ///     causes an overflow.
+    ///   causes an overflow.
     /// - `Ok(None)`: Nothing, if the buffer has room for the added element
     ///
     /// # Examples
@@ -678,15 +5918,33 @@ impl<T: Clone> IsQueue<T> for CircularBuffer<T> {
     /// let mut cbuf_def = CircularBuffer::with_default(3, 5isize);
     /// assert_eq!(cbuf.add(42), Ok(None));
     /// assert_eq!(cbuf_def.add(42), Ok(Some(5)));
+    ///
+    /// // Sustained overflow evicts elements in the order they were added
+    /// let mut cbuf: CircularBuffer<usize> = CircularBuffer::new(100);
+    /// for i in 0..100_000 {
+    ///     let evicted = cbuf.add(i).unwrap();
+    ///     assert_eq!(evicted, i.checked_sub(100));
+    /// }
+    /// assert_eq!(cbuf.size(), 100);
+    /// assert_eq!(cbuf.capacity(), 100);
+    /// assert_eq!(cbuf.peek(), Ok(99_900));
+    /// assert_eq!(cbuf.as_slice(), &(99_900..100_000).collect::<Vec<_>>()[..]);
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0usize);
+    /// let evicted: Vec<_> = (1..=6).map(|i| cbuf_def.add(i).unwrap()).collect();
+    /// assert_eq!(evicted, vec![Some(0), Some(0), Some(0), Some(1), Some(2), Some(3)]);
+    /// assert_eq!(cbuf_def.size(), 3);
     /// ```
     fn add(&mut self, val: T) -> Result<Option<T>, &str> {
-        if self.queue.len() < self.capacity {
-            self.queue.push(val);
-            Ok(None)
+        self.set_newest_age(Some(0));
+        self.queue.push_back(val);
+        let evicted = if self.queue.len() > self.capacity {
+            self.queue.pop_front()
         } else {
-            self.queue.push(val);
-            Ok(Some(self.queue.remove(0usize)))
-        }
+            None
+        };
+        keep_contiguous(&mut self.queue);
+        Ok(evicted)
     }
 
     /// Removes an element from the circular buffer and returns it.
@@ -716,13 +5974,16 @@ impl<T: Clone> IsQueue<T> for CircularBuffer<T> {
     /// assert_eq!(cbuf_def.remove(), Ok(4));
     /// ```
     fn remove(&mut self) -> Result<T, &str> {
-        if !self.queue.is_empty() {
-            if let Some(val) = self.default_value.clone() {
-                self.queue.push(val);
-            };
-            Ok(self.queue.remove(0usize))
-        } else {
-            Err("The Buffer is empty")
+        match self.queue.pop_front() {
+            Some(val) => {
+                if let Some(default) = self.default_value.clone() {
+                    self.queue.push_back(default);
+                    keep_contiguous(&mut self.queue);
+                    self.set_newest_age(self.newest_age.map(|age| age + 1));
+                }
+                Ok(val)
+            }
+            None => Err("The Buffer is empty"),
         }
     }
 
@@ -744,7 +6005,7 @@ impl<T: Clone> IsQueue<T> for CircularBuffer<T> {
     /// assert_eq!(cbuf.peek(), Ok(42));
     /// ```
     fn peek(&self) -> Result<T, &str> {
-        match self.queue.first() {
+        match self.queue.front() {
             Some(val) => Ok(val.clone()),
             None => Err("The Queue is empty"),
         }
@@ -769,4 +6030,124 @@ impl<T: Clone> IsQueue<T> for CircularBuffer<T> {
     fn size(&self) -> usize {
         self.queue.len()
     }
+
+    /// Gets the behavior of the circular buffer when an element is added at
+    /// capacity
+    ///
+    /// # Returns
+    /// `OverflowBehavior::EvictOldest`, as additions to a full circular
+    /// buffer push out its oldest element
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// assert_eq!(cbuf.overflow_behavior(), OverflowBehavior::EvictOldest);
+    /// ```
+    fn overflow_behavior(&self) -> OverflowBehavior {
+        OverflowBehavior::EvictOldest
+    }
+}
+
+impl<T: Clone> IntoIterator for CircularBuffer<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    /// Consumes the circular buffer, iterating over its elements
+    ///
+    /// For buffers with default values, any default-filled slots are
+    /// included.
+    ///
+    /// # Returns
+    /// An iterator over the elements of the buffer, oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// cbuf.add(4);
+    /// assert_eq!(cbuf.into_iter().collect::<Vec<_>>(), vec![2, 3, 4]);
+    ///
+    /// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+    /// cbuf_def.add(1);
+    /// assert_eq!(cbuf_def.into_iter().collect::<Vec<_>>(), vec![0, 0, 1]);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        Vec::from(self.queue).into_iter()
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq for CircularBuffer<T> {
+    /// Compares two circular buffers for equality
+    ///
+    /// Two circular buffers are equal when they have the same `capacity` and
+    /// default value (or both have none), and hold equal elements in the same
+    /// FIFO order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut small: CircularBuffer<isize> = CircularBuffer::new(2);
+    /// let mut large: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// small.add(1);
+    /// large.add(1);
+    /// assert_ne!(small, large);
+    ///
+    /// let mut other: CircularBuffer<isize> = CircularBuffer::new(2);
+    /// other.add(1);
+    /// assert_eq!(small, other);
+    ///
+    /// // Default values must match too
+    /// assert_eq!(CircularBuffer::with_default(2, 0isize), CircularBuffer::with_default(2, 0isize));
+    /// assert_ne!(CircularBuffer::with_default(2, 0isize), CircularBuffer::from_slice_tail(2, &[0, 0]));
+    /// ```
+    fn eq(&self, other: &CircularBuffer<T>) -> bool {
+        self.capacity == other.capacity && self.default_value == other.default_value && self.queue == other.queue
+    }
+}
+
+impl<T: Clone + Eq> Eq for CircularBuffer<T> {}
+
+impl<T: Clone> std::ops::Index<usize> for CircularBuffer<T> {
+    type Output = T;
+
+    /// Gets a reference to the element at a position in the circular buffer
+    ///
+    /// Position `0` is the head of the circular buffer, the element `peek` returns.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// assert_eq!(cbuf[0], cbuf.peek().unwrap());
+    /// assert_eq!(cbuf[2], 3);
+    /// ```
+    ///
+    /// ```should_panic
+    /// # use queues::*;
+    /// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(3);
+    /// cbuf.add(1);
+    /// cbuf.add(2);
+    /// cbuf.add(3);
+    /// let _ = cbuf[3];
+    /// ```
+    fn index(&self, index: usize) -> &T {
+        let size = self.queue.len();
+        self.queue
+            .get(index)
+            .unwrap_or_else(|| panic!("index out of bounds: the size is {} but the index is {}", size, index))
+    }
 }
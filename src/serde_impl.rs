@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+
+use serde::de::{Deserialize, Deserializer, Error};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{Buffer, CircularBuffer, Queue};
+
+/// Serialized form of a `Buffer<T>`, generic over how the elements are held
+/// so that serializing can borrow them
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Buffer")]
+struct BufferRepr<Q> {
+    capacity: usize,
+    queue: Q,
+}
+
+/// Serialized form of a `CircularBuffer<T>`, generic over how the default
+/// value and elements are held so that serializing can borrow them
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "CircularBuffer")]
+struct CircularBufferRepr<V, Q> {
+    capacity: usize,
+    default_value: Option<V>,
+    queue: Q,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    newest_age: Option<usize>,
+}
+
+/// Serializes a queue as the sequence of its elements, oldest first
+///
+/// Registered callbacks are not serialized.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate queues;
+/// # use queues::*;
+/// # fn main() {
+/// let q = queue![1isize, 2, 3];
+/// let json = serde_json::to_string(&q).unwrap();
+/// assert_eq!(json, "[1,2,3]");
+///
+/// let restored: Queue<isize> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(restored, q);
+/// # }
+/// ```
+impl<T: Clone + Serialize> Serialize for Queue<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.queue.serialize(serializer)
+    }
+}
+
+impl<'de, T: Clone + Deserialize<'de>> Deserialize<'de> for Queue<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut q = Queue::new();
+        q.queue = VecDeque::deserialize(deserializer)?;
+        Ok(q)
+    }
+}
+
+/// Serializes a buffer as its capacity and its elements, oldest first
+///
+/// Watermarks and their callbacks are not serialized. Deserialization
+/// rejects data holding more elements than the capacity allows.
+///
+/// # Examples
+///
+/// ```
+/// # use queues::*;
+/// let mut buf: Buffer<isize> = Buffer::new(3);
+/// buf.add(1);
+/// buf.add(2);
+/// let json = serde_json::to_string(&buf).unwrap();
+/// assert_eq!(json, r#"{"capacity":3,"queue":[1,2]}"#);
+///
+/// let restored: Buffer<isize> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(restored, buf);
+///
+/// let overfull = r#"{"capacity":1,"queue":[1,2]}"#;
+/// assert!(serde_json::from_str::<Buffer<isize>>(overfull).is_err());
+/// ```
+impl<T: Clone + Serialize> Serialize for Buffer<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BufferRepr {
+            capacity: self.capacity,
+            queue: &self.queue,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Clone + Deserialize<'de>> Deserialize<'de> for Buffer<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = BufferRepr::<VecDeque<T>>::deserialize(deserializer)?;
+        if repr.queue.len() > repr.capacity {
+            return Err(D::Error::custom(format!(
+                "{} elements exceed the buffer's capacity of {}",
+                repr.queue.len(),
+                repr.capacity
+            )));
+        }
+
+        let mut buf = Buffer::new(repr.capacity);
+        buf.queue = repr.queue;
+        Ok(buf)
+    }
+}
+
+/// Serializes a circular buffer as its capacity, default value and elements,
+/// oldest first
+///
+/// Deserialization rejects data holding more elements than the capacity
+/// allows, or, for buffers with default values, fewer. The optional
+/// `newest_age` records how many elements have entered the buffer since the
+/// newest added one, and is rejected if it is not less than the capacity.
+/// When it is absent from a buffer with default values, the newest element
+/// that differs from the default value is taken to be the newest added one,
+/// which is why deserializing requires `T: PartialEq`.
+///
+/// # Examples
+///
+/// ```
+/// # use queues::*;
+/// let mut cbuf: CircularBuffer<isize> = CircularBuffer::new(2);
+/// cbuf.add(1);
+/// cbuf.add(2);
+/// cbuf.add(3);
+/// let json = serde_json::to_string(&cbuf).unwrap();
+/// let restored: CircularBuffer<isize> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(restored, cbuf);
+///
+/// let mut cbuf_def = CircularBuffer::with_default(3, 0isize);
+/// cbuf_def.add(5);
+/// let json = serde_json::to_string(&cbuf_def).unwrap();
+/// let restored: CircularBuffer<isize> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(restored, cbuf_def);
+/// assert_eq!(restored.has_real_data(), cbuf_def.has_real_data());
+///
+/// let too_old = r#"{"capacity":2,"default_value":0,"queue":[0,5],"newest_age":2}"#;
+/// assert!(serde_json::from_str::<CircularBuffer<isize>>(too_old).is_err());
+///
+/// let missing_age = r#"{"capacity":3,"default_value":0,"queue":[5,0,0]}"#;
+/// let restored: CircularBuffer<isize> = serde_json::from_str(missing_age).unwrap();
+/// assert!(restored.has_real_data());
+///
+/// let only_defaults = r#"{"capacity":2,"default_value":0,"queue":[0,0]}"#;
+/// let restored: CircularBuffer<isize> = serde_json::from_str(only_defaults).unwrap();
+/// assert!(!restored.has_real_data());
+///
+/// let no_default = r#"{"capacity":2,"default_value":null,"queue":[1]}"#;
+/// let restored: CircularBuffer<isize> = serde_json::from_str(no_default).unwrap();
+/// assert_eq!(restored.to_vec(), vec![1]);
+/// ```
+impl<T: Clone + Serialize> Serialize for CircularBuffer<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CircularBufferRepr {
+            capacity: self.capacity,
+            default_value: self.default_value.as_ref(),
+            queue: &self.queue,
+            newest_age: self.newest_age,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Clone + PartialEq + Deserialize<'de>> Deserialize<'de> for CircularBuffer<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = CircularBufferRepr::<T, VecDeque<T>>::deserialize(deserializer)?;
+        let size = repr.queue.len();
+        if size > repr.capacity || (repr.default_value.is_some() && size != repr.capacity) {
+            return Err(D::Error::custom(format!(
+                "{} elements do not fit a circular buffer with a capacity of {}",
+                size, repr.capacity
+            )));
+        }
+        if let Some(age) = repr.newest_age.filter(|&age| age >= repr.capacity) {
+            return Err(D::Error::custom(format!(
+                "a newest element age of {} does not fit a circular buffer with a capacity of {}",
+                age, repr.capacity
+            )));
+        }
+
+        let newest_age = match (repr.newest_age, &repr.default_value) {
+            (Some(age), _) => Some(age),
+            (None, Some(default)) => repr.queue.iter().rev().position(|val| val != default),
+            (None, None) => None,
+        };
+
+        Ok(CircularBuffer {
+            queue: repr.queue,
+            capacity: repr.capacity,
+            default_value: repr.default_value,
+            newest_age,
+        })
+    }
+}